@@ -1,5 +1,3 @@
-import tkinter as tk
-from tkinter import ttk, messagebox, filedialog, Toplevel
 import psutil
 import platform
 import time
@@ -7,17 +5,68 @@ import datetime
 import threading
 import queue
 import json
+import gzip
+import io
 import os
+import sys
 import math
+import signal
+import socket
+import re
+import fnmatch
+import argparse
+try:
+    import curses
+except ImportError:  # stock Windows Python has no curses
+    curses = None
 
-# matplotlib for embedded mini-charts
-from matplotlib.backends.backend_tkagg import FigureCanvasTkAgg
-from matplotlib.figure import Figure
+# Tk and matplotlib are only needed by the GUI; headless mode must work without them
+try:
+    import tkinter as tk
+    from tkinter import ttk, messagebox, filedialog, simpledialog, Toplevel
+    # matplotlib for embedded mini-charts
+    from matplotlib.backends.backend_tkagg import FigureCanvasTkAgg
+    from matplotlib.figure import Figure
+    GUI_IMPORT_ERROR = None
+except ImportError as e:
+    GUI_IMPORT_ERROR = e
 APP_NAME = "Vanilla LOOK"
 APP_VERSION = "v1.0.0"
 AUTHOR = "Camila Rose"
 UPDATE_INTERVAL_MS = 1000  # main update interval
+REPLAY_SPEEDS = ["0.25x", "0.5x", "1x", "2x", "4x", "10x", "60x"]
 CHART_POINTS = 60  # how many points to show in mini charts
+ESCALATE_SECONDS = 5  # default grace period between SIGTERM and SIGKILL
+# Leak detector: RSS is sampled every LEAK_SAMPLE_SECONDS into a per-process history, and a
+# process is flagged once its RSS has grown along a straight-ish line (r² >= LEAK_MIN_R2) for
+# at least LEAK_MIN_MINUTES at LEAK_MIN_GROWTH bytes/hour or more.
+LEAK_SAMPLE_SECONDS = 30
+LEAK_HISTORY_POINTS = 480  # 4 hours
+LEAK_MIN_MINUTES = 30
+LEAK_MIN_GROWTH = 5 * 1024 * 1024
+LEAK_MIN_R2 = 0.8
+SORT_KEYS = ["cpu", "memory", "uss", "pss", "disk I/O", "cpu time"]
+FULL_MEMORY_TOP_N = 100  # USS/PSS/swap are read (slowly, from smaps) for this many largest processes; 0 = all
+PROC_COLUMNS = ("pid","name","user","cpu","cputime","cpuusr","cpusys","mem","rss","threads","read","write","status","leak")
+# optional columns, picked with the column chooser
+EXTRA_COLUMNS = ("ppid","nice","ionice","affinity","started","cmdline","fds","ctxsw","uss","pss","swap","terminal","cwd")
+TREE_COLUMNS = ("tcpu","trss","tthreads","tcount")  # subtree totals, shown in tree view only (tcount in group view too)
+PROC_HEADINGS = [
+    ("pid","PID"),("name","Name"),("user","User"),("cpu","CPU%"),("cputime","CPU Time"),("cpuusr","User CPU"),("cpusys","Sys CPU"),
+    ("mem","Mem%"),("rss","RSS"),("threads","Threads"),("read","Read/s"),("write","Write/s"),("status","Status"),
+    ("leak","Leak?"),("ppid","PPID"),("nice","Nice"),("ionice","I/O Priority"),("affinity","Affinity"),("started","Started"),("cmdline","Command Line"),
+    ("fds","Open FDs"),("ctxsw","Ctx Switches"),("uss","USS"),("pss","PSS"),("swap","Swap"),("terminal","TTY"),("cwd","CWD"),
+    ("tcpu","Tree CPU%"),("trss","Tree RSS"),("tthreads","Tree Threads"),("tcount","Procs")]
+# table columns whose first heading click sorts A-Z instead of biggest first
+TEXT_SORT_COLUMNS = {"pid","name","user","status","ppid","ionice","affinity","started","cmdline","terminal","cwd",
+                     "mount","device","fstype","mounts","state","duplex","addr","event"}
+# io_counters(), ionice() and cpu_affinity() don't exist on macOS; asking process_iter for them there raises ValueError
+PROC_ATTRS = ['pid','ppid','name','exe','username','cmdline','cpu_percent','cpu_times','memory_info','memory_percent','num_threads','status','nice'] + \
+    [a for a in ('io_counters','ionice','cpu_affinity') if hasattr(psutil.Process, a)]
+# Optional columns that cost an extra call per process: only sampled while shown (SystemSampler.extra_attrs)
+COLUMN_ATTRS = {col: attr for col, attr in [
+    ("fds", "num_fds" if hasattr(psutil.Process, "num_fds") else "num_handles"),
+    ("ctxsw", "num_ctx_switches"), ("terminal", "terminal"), ("cwd", "cwd")] if hasattr(psutil.Process, attr)}
 
 # -----------------------
 # Helper utilities
@@ -39,16 +88,315 @@ def human_bytes(num, suffix='B'):
         num /= 1024.0
     return f"{num:.1f}Y{suffix}"
 
+def rate_str(rate, fmt="{:.1f}"):
+    """Format a per-second rate that may be None (first sample, counter reset)."""
+    return "--" if rate is None else fmt.format(rate)
+
+def format_cpu_time(seconds):
+    """Cumulative CPU seconds as "H:MM:SS", or "M:SS.hh" below an hour (like top's TIME+)."""
+    if seconds is None:
+        return "--"
+    if seconds >= 3600:
+        h, rem = divmod(int(seconds), 3600)
+        return f"{h}:{rem // 60:02d}:{rem % 60:02d}"
+    m, sec = divmod(seconds, 60)
+    return f"{int(m)}:{sec:05.2f}"
+
+def format_start_time(ts):
+    """Process create_time as local "YYYY-MM-DD HH:MM:SS" (sorts correctly as text)."""
+    if ts is None:
+        return "--"
+    return datetime.datetime.fromtimestamp(ts).strftime("%Y-%m-%d %H:%M:%S")
+
+def pct_str(value):
+    return "--" if value is None else f"{value:.1f}"
+
+def io_rate_str(rate):
+    """bytes/s that may be None (no access to the counters, first sample)."""
+    return "--" if rate is None else f"{human_bytes(rate)}/s"
+
+_BYTES_RE = re.compile(r"^(?:↑\s*)?(-?\d+(?:\.\d+)?)\s*([KMGTPEZY]?)B(?:/s|/h)?$")
+_NUMBER_RE = re.compile(r"^(-?\d+(?:\.\d+)?)\s*(?:%|ms|Mb/s)?$")
+_TIME_RE = re.compile(r"^(\d+):(\d+)(?::(\d+))?(\.\d+)?$")
+
+def display_sort_key(text):
+    """Sort key for a value as shown in a table: "1.5MB", "12.3%", "1:02.33" and "42" compare
+    as numbers, anything else case-insensitively as text. None for blanks ("", "--", "N/A")."""
+    text = str(text).strip() if text is not None else ""
+    if text in ("", "-", "--", "N/A"):
+        return None
+    m = _BYTES_RE.match(text)
+    if m:
+        return (0, float(m.group(1)) * 1024 ** "_KMGTPEZY".index(m.group(2) or "_"))
+    m = _NUMBER_RE.match(text)
+    if m:
+        return (0, float(m.group(1)))
+    m = _TIME_RE.match(text)
+    if m:
+        a, b, c, frac = m.groups()
+        secs = int(a) * 3600 + int(b) * 60 + int(c) if c else int(a) * 60 + int(b)
+        return (0, secs + float(frac or 0))
+    return (1, text.lower())
+
+def sort_table_rows(rows, index, descending, secondary=0):
+    """Sort Treeview rows [(iid, parent, text, values), ...] by values[index] using
+    display_sort_key, then by values[secondary] ascending. Children stay under their parent
+    and are sorted among their siblings; blank values always go last."""
+    def key(row):
+        values = row[3]
+        return display_sort_key(values[secondary]) if secondary < len(values) else None
+    by_parent = {}
+    for row in sorted(rows, key=lambda r: key(r) or (2, "")):
+        by_parent.setdefault(row[1], []).append(row)
+    for parent, group in by_parent.items():
+        present = [(display_sort_key(r[3][index]) if index < len(r[3]) else None, r) for r in group]
+        blank = [r for k, r in present if k is None]
+        present = [(k, r) for k, r in present if k is not None]
+        present.sort(key=lambda kr: kr[0], reverse=descending)  # stable, so secondary order holds within ties
+        by_parent[parent] = [r for _, r in present] + blank
+    out = []
+    stack = list(reversed(by_parent.get("", [])))
+    while stack:
+        row = stack.pop()
+        out.append(row)
+        stack.extend(reversed(by_parent.get(row[0], [])))
+    return out
+
+def network_lines(io, rates):
+    """Text lines describing aggregate network counters and their per-second rates."""
+    rates = rates or {}
+    return [
+        f"Bytes Sent: {human_bytes(io['bytes_sent'])} (≈ {human_bytes(rates.get('bytes_sent_per_sec') or 0)}/s)",
+        f"Bytes Recv: {human_bytes(io['bytes_recv'])} (≈ {human_bytes(rates.get('bytes_recv_per_sec') or 0)}/s)",
+        f"Packets Sent: {io.get('packets_sent')} (≈ {rate_str(rates.get('packets_sent_per_sec'))}/s)",
+        f"Packets Recv: {io.get('packets_recv')} (≈ {rate_str(rates.get('packets_recv_per_sec'))}/s)",
+        f"Errors In/Out: {io.get('errin')} / {io.get('errout')} (≈ {rate_str(rates.get('errin_per_sec'))} / {rate_str(rates.get('errout_per_sec'))}/s)",
+        f"Drops In/Out: {io.get('dropin')} / {io.get('dropout')} (≈ {rate_str(rates.get('dropin_per_sec'))} / {rate_str(rates.get('dropout_per_sec'))}/s)",
+    ]
+
+def address_family_name(family):
+    if family == socket.AF_INET:
+        return "IPv4"
+    if family == getattr(socket, "AF_INET6", None):
+        return "IPv6"
+    if family == getattr(psutil, "AF_LINK", None):
+        return "MAC"
+    return str(family)
+
+def duplex_name(duplex):
+    return {getattr(psutil, "NIC_DUPLEX_FULL", 2): "full",
+            getattr(psutil, "NIC_DUPLEX_HALF", 1): "half"}.get(duplex, "unknown")
+
+def block_device_name(device):
+    """"/dev/mapper/vg-root" -> "dm-0", "/dev/sda1" -> "sda1": the name disk_io_counters(perdisk=True) uses."""
+    if not device:
+        return None
+    return os.path.basename(os.path.realpath(device)) if device.startswith("/dev/") else os.path.basename(device)
+
+def parent_block_device(name, known):
+    """Whole-disk name for a partition ("sda1" -> "sda", "nvme0n1p2" -> "nvme0n1"), or None."""
+    sys_path = f"/sys/class/block/{name}"
+    if os.path.exists(os.path.join(sys_path, "partition")):
+        return os.path.basename(os.path.dirname(os.path.realpath(sys_path)))
+    stripped = name.rstrip("0123456789")
+    for candidate in (stripped, stripped[:-1] if stripped.endswith("p") else None):
+        if candidate and candidate != name and candidate in known:
+            return candidate
+    return None
+
+def trim_processes(s, top_n):
+    """Shallow copy of snapshot s keeping only its top_n processes by CPU, for exports.
+    Returns s unchanged when top_n is falsy or the snapshot has no process list."""
+    if not top_n or 'processes' not in s:
+        return s
+    trimmed = dict(s)
+    trimmed['processes'] = sorted(s['processes'], key=lambda x: (x.get('cpu_percent') or 0.0, x.get('memory_percent') or 0.0),
+                                  reverse=True)[:top_n]
+    return trimmed
+
+def extra_process_fields(info):
+    """Snapshot fields for the optional COLUMN_ATTRS present in a Process.as_dict() result."""
+    fields = {}
+    if "num_fds" in info or "num_handles" in info:
+        fields["num_fds"] = info.get("num_fds", info.get("num_handles"))
+    if "num_ctx_switches" in info:
+        ctx = info["num_ctx_switches"]
+        fields["ctx_switches"] = ctx.voluntary + ctx.involuntary if ctx else None
+    for key in ("terminal", "cwd"):
+        if key in info:
+            fields[key] = info[key]
+    return fields
+
+def counters_dict(c):
+    """namedtuple/struct of counters -> plain dict (None stays None)."""
+    if c is None:
+        return None
+    return c._asdict() if hasattr(c, "_asdict") else dict(vars(c))
+
 # -----------------------
 # Monitoring backend
 # -----------------------
+class RateEngine:
+    """Turns cumulative counters into per-second rates.
+
+    Each counter source (e.g. "net", "disk", later one per NIC or disk) remembers its last
+    values and the monotonic time they were read, so rates use the real elapsed time no matter
+    how often or from which thread snapshot() is called."""
+    WRAP_32 = 2 ** 32
+
+    def __init__(self):
+        self.prev = {}  # source -> (monotonic time, {counter: value})
+
+    def update(self, source, counters, now=None):
+        """Record counters for source and return {"<counter>_per_sec": rate, ..., "interval_sec": dt}.
+        Rates are None on the first sample of a source and for counters that went backwards
+        because the source was reset (a 32-bit wrap is unwrapped instead)."""
+        now = time.monotonic() if now is None else now
+        counters = {k: v for k, v in (counters or {}).items() if isinstance(v, (int, float))}
+        prev = self.prev.get(source)
+        self.prev[source] = (now, counters)
+        rates = {f"{k}_per_sec": None for k in counters}
+        rates["interval_sec"] = None
+        if prev is None:
+            return rates
+        dt = now - prev[0]
+        if dt <= 0:
+            return rates
+        rates["interval_sec"] = dt
+        for k, cur in counters.items():
+            old = prev[1].get(k)
+            if old is None:
+                continue
+            delta = cur - old
+            if delta < 0:
+                if self.WRAP_32 // 2 <= old < self.WRAP_32 and cur < self.WRAP_32 // 2:
+                    delta += self.WRAP_32  # 32-bit counter wrapped
+                else:
+                    continue  # counter was reset (driver reload, NIC re-created...)
+            rates[f"{k}_per_sec"] = delta / dt
+        return rates
+
+    def prune(self, prefix, alive):
+        """Drop sources under prefix that are not in alive, e.g. NICs that disappeared,
+        so a device that comes back starts from a fresh baseline."""
+        for key in [k for k in self.prev if isinstance(k, tuple) and k[0] == prefix and k not in alive]:
+            del self.prev[key]
+
 class SystemSampler:
     """Collects snapshots of system statistics using psutil."""
-    def __init__(self):
-        self.prev_net = psutil.net_io_counters(pernic=False)
-        self.prev_disk = psutil.disk_io_counters()
+    def __init__(self, full_memory_top=FULL_MEMORY_TOP_N):
+        self.full_memory_top = full_memory_top
+        self.rates = RateEngine()
+        self.rates.update("net", counters_dict(psutil.net_io_counters(pernic=False)))
+        self.rates.update("disk", counters_dict(psutil.disk_io_counters()))
+        for name, c in (psutil.net_io_counters(pernic=True) or {}).items():
+            self.rates.update(("nic", name), counters_dict(c))
+        for name, c in (psutil.disk_io_counters(perdisk=True) or {}).items():
+            self.rates.update(("blk", name), counters_dict(c))
+        self.proc_cache = {}  # pid -> (psutil.Process, create_time)
+        self.extra_attrs = frozenset()  # psutil attrs from COLUMN_ATTRS to sample as well
+        self.full_memory = False  # read USS/PSS/swap (see _add_full_memory); set while a view uses them
+        self.proc_seen = None  # (pid, create_time) -> what process_events needs; None before the first sample
+        self.rss_history = {}  # (pid, create_time) -> [(time.time(), rss)], see LEAK_SAMPLE_SECONDS
+        self.leak_rates = {}  # (pid, create_time) -> suspected leak in bytes/hour, or None
         self.lock = threading.Lock()
 
+    def _iter_processes(self):
+        """Yield (Process, create_time, is_new) for every live process.
+
+        Process objects are cached by PID so cpu_percent() is measured against this process's
+        previous sample; a PID whose create_time changed was reused and gets a fresh object."""
+        pids = psutil.pids()
+        for pid in pids:
+            cached = self.proc_cache.get(pid)
+            try:
+                if cached is not None:
+                    proc, ctime = cached
+                    if proc.is_running():  # also False when the PID was reused
+                        yield proc, ctime, False
+                        continue
+                proc = psutil.Process(pid)
+                ctime = proc.create_time()
+            except (psutil.NoSuchProcess, psutil.AccessDenied):
+                self.proc_cache.pop(pid, None)
+                continue
+            self.proc_cache[pid] = (proc, ctime)
+            yield proc, ctime, True
+        live = set(pids)
+        for pid in [pid for pid in self.proc_cache if pid not in live]:
+            del self.proc_cache[pid]
+
+    def _disk_devices(self, partitions):
+        """Per-device I/O: throughput, IOPS, average latency and utilization, plus the
+        mountpoints that live on each device. Also tags each partition with its io_device."""
+        try:
+            perdisk = psutil.disk_io_counters(perdisk=True) or {}
+        except (OSError, RuntimeError):
+            perdisk = {}
+        read_at = time.monotonic()
+        mounts = {}
+        for p in partitions:
+            name = block_device_name(p.get('device'))
+            p["io_device"] = name if name in perdisk else None
+            for dev in (name, parent_block_device(name, perdisk) if name else None):
+                if dev in perdisk:
+                    mounts.setdefault(dev, []).append(p['mountpoint'])
+        devices = []
+        for name in sorted(perdisk):
+            io = counters_dict(perdisk[name])
+            rates = self.rates.update(("blk", name), io, now=read_at)
+            r_ops, w_ops = rates.get('read_count_per_sec'), rates.get('write_count_per_sec')
+            r_ms, w_ms = rates.get('read_time_per_sec'), rates.get('write_time_per_sec')
+            busy = rates.get('busy_time_per_sec')  # Linux/FreeBSD only
+            devices.append({
+                "name": name,
+                "partitions": mounts.get(name, []),
+                "io": io,
+                "rates": rates,
+                "read_iops": r_ops,
+                "write_iops": w_ops,
+                # ms of I/O time per completed request over the interval
+                "read_latency_ms": r_ms / r_ops if r_ops and r_ms is not None else None,
+                "write_latency_ms": w_ms / w_ops if w_ops and w_ms is not None else None,
+                "util_percent": min(busy / 10.0, 100.0) if busy is not None else None,
+            })
+        self.rates.prune("blk", {("blk", name) for name in perdisk})
+        return devices
+
+    def _interfaces(self):
+        """Per-NIC counters, rates, addresses and link state."""
+        pernic = psutil.net_io_counters(pernic=True) or {}
+        read_at = time.monotonic()
+        try:
+            addrs = psutil.net_if_addrs()
+        except (OSError, psutil.AccessDenied):
+            addrs = {}
+        try:
+            stats = psutil.net_if_stats()
+        except (OSError, psutil.AccessDenied):
+            stats = {}
+        interfaces = []
+        for name in sorted(set(pernic) | set(stats)):
+            io = counters_dict(pernic.get(name))
+            st = stats.get(name)
+            interfaces.append({
+                "name": name,
+                "isup": st.isup if st else None,
+                "speed_mbps": st.speed if st else None,
+                "mtu": st.mtu if st else None,
+                "duplex": duplex_name(st.duplex) if st else None,
+                "addresses": [{
+                    "family": address_family_name(a.family),
+                    "address": a.address,
+                    "netmask": a.netmask,
+                    "broadcast": a.broadcast,
+                } for a in addrs.get(name, [])],
+                "io": io,
+                "rates": self.rates.update(("nic", name), io, now=read_at) if io else {},
+            })
+        self.rates.prune("nic", {("nic", name) for name in pernic})
+        return interfaces
+
     def snapshot(self):
         """Return a dictionary snapshot of current system state, including CPU, memory, disk, and network data."""
         with self.lock:
@@ -84,42 +432,79 @@ class SystemSampler:
                     }
                 })
             disk_io = psutil.disk_io_counters()
+            disk_read_at = time.monotonic()
+            devices = self._disk_devices(partitions)
 
             # Network info
             net_io = psutil.net_io_counters(pernic=False)
+            net_read_at = time.monotonic()
+            interfaces = self._interfaces()
 
-            # Processes (top 50)
+            # Logged-in sessions
+            try:
+                sessions = [{"name": u.name, "terminal": u.terminal, "host": u.host, "started": u.started,
+                             "pid": getattr(u, "pid", None)} for u in psutil.users()]
+            except (OSError, psutil.Error):  # e.g. containers without utmp
+                sessions = []
+
+            # Processes (all of them; exports can trim with trim_processes)
             procs = []
-            for p in psutil.process_iter(['pid','name','username','cpu_percent','memory_info','memory_percent','status']):
+            alive = set()
+            attrs = PROC_ATTRS + sorted(self.extra_attrs)
+            now = time.time()
+            for p, ctime, is_new in self._iter_processes():
                 try:
-                    info = p.info
+                    with p.oneshot():
+                        info = p.as_dict(attrs, ad_value=None)
+                    pio = info.get('io_counters')
+                    ctimes = info.get('cpu_times')
+                    io_rates = {}
+                    if pio is not None:
+                        key = ("proc", info.get('pid'), ctime)
+                        alive.add(key)
+                        io_rates = self.rates.update(key, {"read_bytes": pio.read_bytes, "write_bytes": pio.write_bytes})
                     procs.append({
                         "pid": info.get('pid'),
+                        "ppid": info.get('ppid'),
                         "name": info.get('name'),
+                        "exe": info.get('exe') or None,
                         "username": info.get('username'),
-                        "cpu_percent": info.get('cpu_percent'),
+                        "cmdline": " ".join(info['cmdline']) if info.get('cmdline') else None,
+                        # a new Process object's first cpu_percent() has no baseline and is always 0.0
+                        "cpu_percent": None if is_new else info.get('cpu_percent'),
+                        "cpu_user": ctimes.user if ctimes else None,
+                        "cpu_system": ctimes.system if ctimes else None,
+                        "cpu_time": ctimes.user + ctimes.system if ctimes else None,
+                        "create_time": ctime,
                         "memory_percent": info.get('memory_percent'),
                         "memory_rss": info['memory_info'].rss if info.get('memory_info') else None,
-                        "status": info.get('status')
+                        "status": info.get('status'),
+                        "num_threads": info.get('num_threads'),
+                        "io_read_bytes": pio.read_bytes if pio else None,
+                        "io_write_bytes": pio.write_bytes if pio else None,
+                        "io_read_per_sec": io_rates.get('read_bytes_per_sec'),
+                        "io_write_per_sec": io_rates.get('write_bytes_per_sec'),
+                        "nice": int(info['nice']) if info.get('nice') is not None else None,
+                        "ionice": ionice_label(info.get('ionice')),
+                        "cpu_affinity": info.get('cpu_affinity'),
+                        "leak_rate": self._track_rss((info.get('pid'), ctime), now, info['memory_info'].rss if info.get('memory_info') else None),
                     })
+                    if self.extra_attrs:
+                        procs[-1].update(extra_process_fields(info))
                 except (psutil.NoSuchProcess, psutil.AccessDenied):
+                    self.proc_cache.pop(p.pid, None)
                     continue
-            procs_sorted = sorted(procs, key=lambda x: (x.get('cpu_percent') or 0.0, x.get('memory_percent') or 0.0), reverse=True)[:50]
+            self.rates.prune("proc", alive)
+            self._add_full_memory(procs)
+            events = self._process_events(procs, t)
+            for key in [k for k in self.rss_history if k not in self.proc_seen]:
+                del self.rss_history[key]
+                self.leak_rates.pop(key, None)
+            procs_sorted = sorted(procs, key=lambda x: (x.get('cpu_percent') or 0.0, x.get('memory_percent') or 0.0), reverse=True)
 
-            # I/O rates (delta)
-            time_interval = 1.0
-            net_delta = {
-                "bytes_sent_per_sec": (net_io.bytes_sent - self.prev_net.bytes_sent)/time_interval if self.prev_net else None,
-                "bytes_recv_per_sec": (net_io.bytes_recv - self.prev_net.bytes_recv)/time_interval if self.prev_net else None,
-            }
-            disk_delta = {
-                "read_bytes_per_sec": (disk_io.read_bytes - self.prev_disk.read_bytes)/time_interval if self.prev_disk else None,
-                "write_bytes_per_sec": (disk_io.write_bytes - self.prev_disk.write_bytes)/time_interval if self.prev_disk else None,
-            }
-
-            # save previous
-            self.prev_net = net_io
-            self.prev_disk = disk_io
+            # I/O rates for every cumulative counter, over the real time between the counter reads
+            net_delta = self.rates.update("net", counters_dict(net_io), now=net_read_at)
+            disk_delta = self.rates.update("disk", counters_dict(disk_io), now=disk_read_at)
 
             snapshot = {
                 "timestamp": t,
@@ -144,36 +529,862 @@ class SystemSampler:
                 },
                 "disk": {
                     "partitions": partitions,
-                    "io": disk_io._asdict() if hasattr(disk_io, "_asdict") else vars(disk_io)
+                    "io": counters_dict(disk_io),
+                    "rates": disk_delta,
+                    "devices": devices
                 },
                 "network": {
-                    "io": net_io._asdict() if hasattr(net_io, "_asdict") else vars(net_io),
-                    "rates": net_delta
+                    "io": counters_dict(net_io),
+                    "rates": net_delta,
+                    "interfaces": interfaces
                 },
-                "processes": procs_sorted
+                "processes": procs_sorted,
+                "events": events,
+                "users": sessions
             }
             return snapshot
 
+    def _add_full_memory(self, procs):
+        """USS, PSS and swap from memory_full_info() for the full_memory_top largest processes by
+        RSS (all of them when it's 0/None). It reads smaps, which is slow and usually denied for
+        other users' processes; those keep None rather than a double-counting RSS figure.
+        Skipped (all None) unless full_memory is set."""
+        for p in procs:
+            p.update(memory_uss=None, memory_pss=None, memory_swap=None)
+        if not self.full_memory:
+            return
+        ranked = sorted(procs, key=lambda p: p.get('memory_rss') or 0, reverse=True)
+        if self.full_memory_top:
+            ranked = ranked[:self.full_memory_top]
+        for p in ranked:
+            cached = self.proc_cache.get(p['pid'])
+            if cached is None:
+                continue
+            try:
+                mem = cached[0].memory_full_info()
+            except (psutil.Error, OSError, AttributeError, NotImplementedError):
+                continue
+            p.update(memory_uss=getattr(mem, "uss", None), memory_pss=getattr(mem, "pss", None),
+                     memory_swap=getattr(mem, "swap", None))
+
+    def _track_rss(self, key, now, rss):
+        """Add rss to the process's history every LEAK_SAMPLE_SECONDS; returns its suspected
+        leak rate in bytes/hour (None when it doesn't look like a leak)."""
+        hist = self.rss_history.setdefault(key, [])
+        if rss is not None and (not hist or now - hist[-1][0] >= LEAK_SAMPLE_SECONDS):
+            hist.append((now, rss))
+            del hist[:-LEAK_HISTORY_POINTS]
+            self.leak_rates[key] = leak_rate(hist)
+        return self.leak_rates.get(key)
+
+    def memory_history(self, pid, create_time):
+        """Copy of the sampled [(time, rss)] history of one process."""
+        with self.lock:
+            return list(self.rss_history.get((pid, create_time), []))
+
+    def _process_events(self, procs, t):
+        """Diff this sample's processes against the previous sample's: a "started" event for
+        every new (pid, create_time), an "exited" one with lifetime, peak RSS and total CPU
+        seconds for every one that is gone. Processes born and gone between two samples are
+        never seen; the first sample reports nothing."""
+        seen = {}
+        for p in procs:
+            key = (p['pid'], p['create_time'])
+            prev = (self.proc_seen or {}).get(key)
+            seen[key] = {
+                "pid": p['pid'], "ppid": p.get('ppid'), "name": p.get('name'), "cmdline": p.get('cmdline'),
+                "create_time": p['create_time'],
+                "peak_rss": max(p.get('memory_rss') or 0, prev['peak_rss'] if prev else 0),
+                "cpu_time": p['cpu_time'] if p.get('cpu_time') is not None else (prev['cpu_time'] if prev else None),
+            }
+        events = []
+        if self.proc_seen is not None:
+            now = time.time()
+            for key, info in seen.items():
+                if key not in self.proc_seen:
+                    events.append({"time": t, "event": "started", "pid": info['pid'], "ppid": info['ppid'],
+                                   "name": info['name'], "cmdline": info['cmdline'], "create_time": info['create_time']})
+            for key, info in self.proc_seen.items():
+                if key not in seen:
+                    events.append(dict(info, time=t, event="exited", lifetime=max(0.0, now - info['create_time'])))
+        self.proc_seen = seen
+        return events
+
+def start_sampler_thread(sampler, out_queue, interval=1.0, stop_event=None):
+    """Put a snapshot (or an error dict) on out_queue every interval from a daemon thread.
+    Both the Tk app and the terminal UI consume this queue."""
+    stop_event = stop_event or threading.Event()
+    def sampler_thread():
+        while not stop_event.is_set():
+            started = time.monotonic()
+            try:
+                snap = sampler.snapshot()
+                out_queue.put(snap)
+            except Exception as e:
+                out_queue.put({"timestamp": now_iso(), "error": str(e)})
+            # sleep only what is left of the interval after the (possibly slow) process scan
+            stop_event.wait(max(0.0, interval - (time.monotonic() - started)))
+    t = threading.Thread(target=sampler_thread, daemon=True)
+    t.start()
+    return t
+
+# -----------------------
+# Session recording
+# -----------------------
+SESSION_DIR = os.path.join(os.path.expanduser("~"), ".vanilla_look", "sessions")
+SESSION_KEEP = 20          # how many session files to keep on disk
+SESSION_ROTATE_MB = 64     # start a new file once the current one reaches this size
+SESSION_ROTATE_MIN = 60    # ...or once it has been open this many minutes
+LOG_LIST_MAX = 1000        # lines kept in the Logs tab listbox
+EVENT_LIST_MAX = 2000      # process events kept in the Events tab
+# chart history attributes of the GUI, swapped out while a recording is replayed
+HISTORY_ATTRS = ("time_history", "cpu_history", "mem_history", "nic_history", "blk_history", "watch_history", "user_history")
+
+def iter_session_file(path):
+    """Yield snapshots from a .jsonl or .jsonl.gz session file, tolerating a truncated tail."""
+    opener = gzip.open if path.endswith(".gz") else open
+    try:
+        with opener(path, "rt", encoding="utf-8") as f:
+            for line in f:
+                line = line.strip()
+                if not line:
+                    continue
+                try:
+                    yield json.loads(line)
+                except json.JSONDecodeError:
+                    # last line of a file that was being written when the app died
+                    return
+    except (EOFError, gzip.BadGzipFile):
+        return
+
+def load_recording(path):
+    """Load snapshots from an exported JSON array, a single JSON snapshot, or a session file.
+    Opening one part of a rotated session loads every part of that session still on disk."""
+    if path.endswith(".json"):
+        with open(path, encoding="utf-8") as f:
+            data = json.load(f)
+        snaps = data if isinstance(data, list) else [data]
+    else:
+        name = os.path.basename(path)
+        stem = name[:-len(".jsonl.gz")] if name.endswith(".jsonl.gz") else name[:-len(".jsonl")]
+        prefix, sep, part = stem.rpartition("_")
+        paths = [path]
+        if name.startswith("session_") and sep and part.isdigit():
+            folder = os.path.dirname(path) or "."
+            paths = [os.path.join(folder, n) for n in sorted(os.listdir(folder), key=session_file_key)
+                     if n.startswith(prefix + "_") and (n.endswith(".jsonl") or n.endswith(".jsonl.gz"))]
+        snaps = [snap for p in paths for snap in iter_session_file(p)]
+    return [snap for snap in snaps if isinstance(snap, dict) and "cpu" in snap and "error" not in snap]
+
+SESSION_NAME_RE = re.compile(r"^session_(\d{8}_\d{6})(?:-(\d+))?_(\d+)\.jsonl(?:\.gz)?$")
+
+def session_file_key(name):
+    """Oldest-first sort key of a session file name: (timestamp, same-second counter, part).
+    Plain string order would put "..._101010-2_001" before "..._101010_001"."""
+    m = SESSION_NAME_RE.match(name)
+    if not m:
+        return (name, 0, 0)
+    stamp, n, part = m.groups()
+    return (stamp, int(n or 1), int(part))
+
+def snapshot_time(s):
+    try:
+        return datetime.datetime.fromisoformat(s['timestamp'])
+    except (KeyError, TypeError, ValueError):
+        return None
+
+class SessionRecorder:
+    """Streams snapshots to JSON Lines session files (optionally gzip-compressed),
+    rotating by size/age and keeping only the newest `keep` files in the directory."""
+    def __init__(self, directory=SESSION_DIR, compress=False, rotate_mb=SESSION_ROTATE_MB,
+                 rotate_minutes=SESSION_ROTATE_MIN, keep=SESSION_KEEP, top_n=None):
+        self.directory = directory
+        self.top_n = top_n  # only record this many processes per snapshot (None = all)
+        self.compress = compress
+        self.rotate_bytes = int(rotate_mb * 1024 * 1024) if rotate_mb else None
+        self.rotate_seconds = rotate_minutes * 60 if rotate_minutes else None
+        self.keep = keep
+        self.lock = threading.Lock()
+        self.session_id = None
+        self.files = []       # files written by the current session, oldest first
+        self._fh = None
+        self._raw = None
+        self._opened_at = 0.0
+        self.count = 0
+
+    @property
+    def active(self):
+        return self._fh is not None
+
+    @property
+    def path(self):
+        return self.files[-1] if self.files else None
+
+    def start(self):
+        """Begin a new session; any previous session is closed."""
+        with self.lock:
+            self._close()
+            os.makedirs(self.directory, exist_ok=True)
+            stamp = datetime.datetime.now().strftime("%Y%m%d_%H%M%S")
+            self.session_id, n = stamp, 1
+            while any(name.startswith(f"session_{self.session_id}_") for name in os.listdir(self.directory)):
+                n += 1
+                self.session_id = f"{stamp}-{n}"
+            self.files = []
+            self.count = 0
+            self._open_part()
+
+    def stop(self):
+        with self.lock:
+            self._close()
+
+    def reset(self):
+        """Forget the current (stopped) session; its files stay on disk."""
+        with self.lock:
+            self._close()
+            self.files = []
+            self.count = 0
+
+    def write(self, snapshot):
+        with self.lock:
+            if self._fh is None:
+                return
+            self._fh.write(json.dumps(trim_processes(snapshot, self.top_n)) + "\n")
+            self._fh.flush()
+            self.count += 1
+            if self._should_rotate():
+                self._close()
+                self._open_part()
+
+    def missing_parts(self):
+        """Files of the current session that are no longer on disk (deleted by hand or by retention)."""
+        with self.lock:
+            return [p for p in self.files if not os.path.exists(p)]
+
+    def snapshots(self):
+        """Yield every snapshot of the current session that is still on disk."""
+        with self.lock:
+            if self._fh is not None:
+                self._fh.flush()
+            files = [p for p in self.files if os.path.exists(p)]
+        for p in files:
+            yield from iter_session_file(p)
+
+    def _should_rotate(self):
+        if self.rotate_seconds and time.monotonic() - self._opened_at >= self.rotate_seconds:
+            return True
+        if self.rotate_bytes:
+            self._raw.flush()
+            if os.path.getsize(self._raw.name) >= self.rotate_bytes:
+                return True
+        return False
+
+    def _open_part(self):
+        ext = ".jsonl.gz" if self.compress else ".jsonl"
+        path = os.path.join(self.directory, f"session_{self.session_id}_{len(self.files) + 1:03d}{ext}")
+        self._raw = open(path, "wb")
+        self._fh = io.TextIOWrapper(gzip.GzipFile(fileobj=self._raw, mode="wb") if self.compress else self._raw,
+                                    encoding="utf-8")
+        self._opened_at = time.monotonic()
+        self.files.append(path)
+        self._enforce_retention()
+
+    def _close(self):
+        if self._fh is not None:
+            fh, self._fh, self._raw = self._fh, None, None
+            try:
+                fh.close()  # closes the gzip stream and the raw file too
+            except OSError:
+                pass  # e.g. disk full while flushing; the part keeps what was written
+
+    def _enforce_retention(self):
+        """Delete the oldest session files beyond `keep`, older parts of the current session
+        included; only the file open for writing is never deleted."""
+        if not self.keep:
+            return
+        names = sorted((n for n in os.listdir(self.directory)
+                        if n.startswith("session_") and (n.endswith(".jsonl") or n.endswith(".jsonl.gz"))),
+                       key=session_file_key)
+        current = os.path.basename(self.path) if self.path else None
+        for n in [n for n in names[:-self.keep] if n != current]:
+            try:
+                os.remove(os.path.join(self.directory, n))
+            except OSError:
+                pass
+
+# -----------------------
+# Process helpers (shared by all frontends)
+# -----------------------
+def process_io_rate(p):
+    """Combined disk read+write bytes/s of a snapshot process (0 when unknown)."""
+    return (p.get('io_read_per_sec') or 0.0) + (p.get('io_write_per_sec') or 0.0)
+
+PROCESS_SORTS = {
+    "cpu": lambda x: x.get('cpu_percent') or 0,
+    "memory": lambda x: x.get('memory_percent') or 0,
+    "uss": lambda x: x.get('memory_uss') or 0,
+    "pss": lambda x: x.get('memory_pss') or 0,
+    "disk I/O": process_io_rate,
+    "cpu time": lambda x: x.get('cpu_time') or 0,
+}
+
+def memory_trend(points):
+    """Least-squares fit of [(time, rss)]: (slope in bytes/second, r²). (0.0, 0.0) for fewer
+    than three points or a flat line."""
+    if len(points) < 3:
+        return 0.0, 0.0
+    t0 = points[0][0]
+    xs = [t - t0 for t, _ in points]
+    ys = [float(v) for _, v in points]
+    mx, my = sum(xs) / len(xs), sum(ys) / len(ys)
+    sxx = sum((x - mx) ** 2 for x in xs)
+    syy = sum((y - my) ** 2 for y in ys)
+    if not sxx or not syy:
+        return 0.0, 0.0
+    sxy = sum((x - mx) * (y - my) for x, y in zip(xs, ys))
+    return sxy / sxx, sxy * sxy / (sxx * syy)
+
+def leak_rate(points):
+    """RSS growth in bytes/hour if points [(time, rss)] look like a leak (see LEAK_*), else None."""
+    if not points or points[-1][0] - points[0][0] < LEAK_MIN_MINUTES * 60:
+        return None
+    slope, r2 = memory_trend(points)
+    per_hour = slope * 3600
+    return per_hour if per_hour >= LEAK_MIN_GROWTH and r2 >= LEAK_MIN_R2 else None
+
+def leak_str(rate):
+    return f"↑ {human_bytes(rate)}/h" if rate else ""
+
+def sort_processes(plist, key):
+    """Sort a snapshot process list in place by one of SORT_KEYS, highest first."""
+    plist.sort(key=PROCESS_SORTS.get(key, PROCESS_SORTS["cpu"]), reverse=True)
+    return plist
+
+# Filter expression fields: name -> (snapshot key, kind). "size" values accept K/M/G/T suffixes.
+FILTER_FIELDS = {
+    "pid": ("pid", "number"),
+    "ppid": ("ppid", "number"),
+    "name": ("name", "text"),
+    "user": ("username", "text"),
+    "cmd": ("cmdline", "text"),
+    "status": ("status", "text"),
+    "cpu": ("cpu_percent", "number"),
+    "mem": ("memory_percent", "number"),
+    "rss": ("memory_rss", "size"),
+    "uss": ("memory_uss", "size"),
+    "pss": ("memory_pss", "size"),
+    "swap": ("memory_swap", "size"),
+    "threads": ("num_threads", "number"),
+    "cputime": ("cpu_time", "number"),
+    "read": ("io_read_per_sec", "size"),
+    "write": ("io_write_per_sec", "size"),
+    "nice": ("nice", "number"),
+    "leak": ("leak_rate", "size"),
+}
+FILTER_TERM_RE = re.compile(r'\s*(-|!)?(?:(\w+)(:|~|>=|<=|!=|==|=|>|<))?("(?:[^"\\]|\\.)*"|\S+)')
+_SIZE_RE = re.compile(r"^(\d+(?:\.\d+)?)([KMGTP]?)(?:i?B)?$", re.IGNORECASE)
+
+class ProcessFilterError(ValueError):
+    """A process filter expression that doesn't parse."""
+
+def _filter_number(text, kind):
+    m = _SIZE_RE.match(text) if kind == "size" else None
+    if m:
+        return float(m.group(1)) * 1024 ** "_KMGTP".index(m.group(2).upper() or "_")
+    try:
+        return float(text)
+    except ValueError:
+        raise ProcessFilterError(f"not a number: {text!r}") from None
+
+def _filter_term(field, op, value):
+    """One predicate p -> bool for field/op/value from a filter expression."""
+    key, kind = FILTER_FIELDS[field]
+    if op == "~":
+        try:
+            rx = re.compile(value, re.IGNORECASE)
+        except re.error as e:
+            raise ProcessFilterError(f"bad regex {value!r}: {e}") from None
+        return lambda p: p.get(key) is not None and rx.search(str(p[key])) is not None
+    if kind == "text":
+        value = value.lower()
+        if op == ":":
+            return lambda p: value in str(p.get(key) or "").lower()
+        if op in ("=", "=="):
+            return lambda p: str(p.get(key) or "").lower() == value
+        if op == "!=":
+            return lambda p: str(p.get(key) or "").lower() != value
+        raise ProcessFilterError(f"{field} is text; use {field}:, {field}=, {field}!= or {field}~")
+    number = _filter_number(value, kind)
+    compare = {":": float.__eq__, "=": float.__eq__, "==": float.__eq__, "!=": float.__ne__,
+               ">": float.__gt__, "<": float.__lt__, ">=": float.__ge__, "<=": float.__le__}[op]
+    return lambda p: p.get(key) is not None and compare(float(p[key]), number)
+
+def parse_process_filter(query):
+    """Compile a filter expression into a predicate over snapshot processes.
+
+    Space-separated terms must all match: `field:text` (contains), `field=value`, `field!=value`,
+    `field~regex`, `field>n` / `<` / `>=` / `<=` for numbers (rss/read/write take K/M/G
+    suffixes), and a bare word matches the name. Quote values with spaces ("..."), prefix a
+    term with - or ! to negate it. Raises ProcessFilterError with a readable message."""
+    query = (query or "").strip()
+    terms = []
+    pos = 0
+    while pos < len(query):
+        m = FILTER_TERM_RE.match(query, pos)
+        if not m or m.end() == pos:
+            raise ProcessFilterError(f"can't parse {query[pos:]!r}")
+        pos = m.end()
+        negate, field, op, value = m.groups()
+        if value.startswith('"'):
+            if len(value) < 2 or not value.endswith('"'):
+                raise ProcessFilterError(f"unterminated quote in {value!r}")
+            value = re.sub(r'\\(.)', r'\1', value[1:-1])
+        if field is None:
+            field, op = "name", ":"
+        elif field.lower() not in FILTER_FIELDS:
+            raise ProcessFilterError(f"unknown field {field!r} (known: {', '.join(FILTER_FIELDS)})")
+        pred = _filter_term(field.lower(), op, value)
+        terms.append((lambda p, f=pred: not f(p)) if negate else pred)
+    return lambda p: all(t(p) for t in terms)
+
+FULL_MEMORY_FIELDS = ("uss", "pss", "swap")  # sort keys, columns and filter fields that need SystemSampler.full_memory
+
+def needs_full_memory(sort_key=None, query="", columns=()):
+    """Whether a process view sorts, filters or shows USS/PSS/swap, so they have to be sampled."""
+    if sort_key in FULL_MEMORY_FIELDS or any(c in FULL_MEMORY_FIELDS for c in columns):
+        return True
+    return any((m.group(2) or "").lower() in FULL_MEMORY_FIELDS for m in FILTER_TERM_RE.finditer(query or ""))
+
+def filter_processes(plist, query):
+    """Return the processes matching a filter expression (see parse_process_filter); a
+    plain word is a case-insensitive substring match on the name."""
+    if not (query or "").strip():
+        return plist
+    pred = parse_process_filter(query)
+    return [p for p in plist if pred(p)]
+
+SETTINGS_DIR = os.path.join(os.path.expanduser("~"), ".vanilla_look")
+SAVED_FILTERS_PATH = os.path.join(SETTINGS_DIR, "filters.json")  # {name: filter expression}
+COLUMNS_PATH = os.path.join(SETTINGS_DIR, "columns.json")  # {"columns": [...], "hidden": [...], "widths": {col: px}}
+
+def load_settings(path):
+    """A JSON object saved by save_settings; empty when there is none yet or it's unreadable."""
+    try:
+        with open(path, encoding="utf-8") as f:
+            data = json.load(f)
+    except (OSError, ValueError):
+        return {}
+    return data if isinstance(data, dict) else {}
+
+def save_settings(data, path):
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    with open(path, "w", encoding="utf-8") as f:
+        json.dump(data, f, indent=2, sort_keys=True)
+
+def saved_proc_columns(saved):
+    """The process table columns from saved column settings. Only the default columns listed
+    under "hidden" stay off, so defaults added by a later version show up in their usual place."""
+    columns = [c for c in saved.get("columns") or [] if c in PROC_COLUMNS + EXTRA_COLUMNS]
+    if not columns:
+        return list(PROC_COLUMNS)
+    hidden = set(saved.get("hidden") or [])
+    for i, c in enumerate(PROC_COLUMNS):
+        if c not in columns and c not in hidden:
+            before = [d for d in PROC_COLUMNS[:i] if d in columns]
+            columns.insert(columns.index(before[-1]) + 1 if before else 0, c)
+    return columns
+
+WATCHLIST_PATH = os.path.join(SETTINGS_DIR, "watchlist.json")  # {"patterns": [...]}; PID pins aren't kept
+WATCH_MAX = 8  # pinned entries; each gets a row of sparklines
+WATCH_METRICS = [("cpu", "CPU %"), ("rss", "RSS MB"), ("io", "Disk I/O KB/s"), ("threads", "Threads")]
+
+def watch_key(entry):
+    """Stable key of a watchlist entry: {"kind": "pid", "pid", "create_time", "name"} or
+    {"kind": "name", "pattern"} (a case-insensitive glob such as "postgres*")."""
+    return f"pid:{entry['pid']}:{entry['create_time']}" if entry["kind"] == "pid" else f"name:{entry['pattern']}"
+
+def watch_label(entry):
+    return f"{entry['name']} (PID {entry['pid']})" if entry["kind"] == "pid" else f"name: {entry['pattern']}"
+
+def watch_sample(entry, plist):
+    """Current cpu/rss/io/threads of a watchlist entry: the pinned process, or the sum over
+    every process whose name matches the pattern. None when nothing matches."""
+    if entry["kind"] == "pid":
+        procs = [p for p in plist if p['pid'] == entry['pid'] and p.get('create_time') == entry['create_time']]
+    else:
+        pattern = entry['pattern'].lower()
+        procs = [p for p in plist if fnmatch.fnmatchcase((p.get('name') or "").lower(), pattern)]
+    if not procs:
+        return None
+    return {
+        "cpu": sum(p.get('cpu_percent') or 0.0 for p in procs),
+        "rss": sum(p.get('memory_rss') or 0 for p in procs),
+        "io": sum(process_io_rate(p) for p in procs),
+        "threads": sum(p.get('num_threads') or 0 for p in procs),
+        "count": len(procs),
+    }
+
+def user_totals(plist):
+    """Per-user sums over snapshot processes: {user: {"procs", "cpu", "mem", "rss", "read",
+    "write"}}. Processes whose owner can't be read are counted under "?"."""
+    totals = {}
+    for p in plist:
+        t = totals.setdefault(p.get('username') or "?", {"procs": 0, "cpu": 0.0, "mem": 0.0, "rss": 0, "read": 0.0, "write": 0.0})
+        t["procs"] += 1
+        t["cpu"] += p.get('cpu_percent') or 0.0
+        t["mem"] += p.get('memory_percent') or 0.0
+        t["rss"] += p.get('memory_rss') or 0
+        t["read"] += p.get('io_read_per_sec') or 0.0
+        t["write"] += p.get('io_write_per_sec') or 0.0
+    return totals
+
+GROUP_IID_PREFIX = "app:"  # proc_tree iids of group-by-app rows; process rows use process_iid
+
+def process_iid(p):
+    """proc_tree iid of a snapshot process. It includes the start time so that a reused PID gets
+    a new row instead of silently taking over a selected one."""
+    return f"{p['pid']}:{p.get('create_time')}"
+
+def _sum_field(procs, key, partial=True):
+    """Sum of procs[key] over the processes that have it; with partial=False, None unless all do."""
+    values = [p[key] for p in procs if p.get(key) is not None]
+    if not partial and len(values) < len(procs):
+        return None
+    return sum(values) if values else None
+
+def process_group_key(p):
+    """What group-by-app puts processes together by: the executable, or the name when it can't be read."""
+    return p.get('exe') or p.get('name') or "?"
+
+def group_totals(name, procs):
+    """A snapshot-process-like dict summing procs (one app, see process_group_key) for a group row.
+    USS/PSS/swap are only read for some processes, so they stay None ("--") unless every member has them."""
+    users = {p.get('username') for p in procs}
+    statuses = {p.get('status') for p in procs}
+    total = {key: _sum_field(procs, key) for key in (
+        "cpu_percent", "cpu_time", "cpu_user", "cpu_system", "memory_percent", "memory_rss",
+        "num_threads", "io_read_per_sec", "io_write_per_sec", "num_fds", "ctx_switches", "leak_rate")}
+    total.update({key: _sum_field(procs, key, partial=False) for key in ("memory_uss", "memory_pss", "memory_swap")})
+    total.update(pid="", name=name, username=users.pop() if len(users) == 1 else "(several)",
+                 status=statuses.pop() if len(statuses) == 1 else None)
+    return total
+
+def build_process_tree(plist):
+    """Nest snapshot processes by ppid.
+
+    Returns (roots, children, totals): root PIDs (parent unknown or not in the list), a
+    pid -> [child pids] map, and pid -> {"cpu", "rss", "threads", "count"} summed over the
+    process and all its descendants."""
+    by_pid = {p['pid']: p for p in plist}
+    children = {}
+    roots = []
+    for p in plist:
+        ppid = p.get('ppid')
+        if ppid in by_pid and ppid != p['pid']:
+            children.setdefault(ppid, []).append(p['pid'])
+        else:
+            roots.append(p['pid'])
+    totals = {}
+    # iterative post-order so deep trees can't hit the recursion limit
+    stack = [(pid, False) for pid in roots]
+    while stack:
+        pid, done = stack.pop()
+        if not done:
+            stack.append((pid, True))
+            stack.extend((c, False) for c in children.get(pid, []))
+            continue
+        p = by_pid[pid]
+        t = {"cpu": p.get('cpu_percent') or 0.0, "rss": p.get('memory_rss') or 0,
+             "threads": p.get('num_threads') or 0, "count": 1}
+        for c in children.get(pid, []):
+            for k in t:
+                t[k] += totals[c][k]
+        totals[pid] = t
+    return roots, children, totals
+
+ACCESS_DENIED = "<access denied>"
+MEMORY_FIELD_LABELS = {  # memory_full_info() fields as shown in the detail window
+    "rss": "RSS (resident)", "vms": "VMS (virtual)", "shared": "Shared", "text": "Text (code)",
+    "lib": "Lib", "data": "Data + stack", "dirty": "Dirty", "uss": "USS (unique to process)",
+    "pss": "PSS (proportional share)", "swap": "Swap",
+}
+
+def _proc_call(fn, default=ACCESS_DENIED):
+    """Call a psutil.Process method, returning default on AccessDenied/unsupported platform.
+    NoSuchProcess propagates so callers can notice that the process exited."""
+    try:
+        return fn()
+    except (psutil.AccessDenied, psutil.ZombieProcess):
+        return default
+    except (AttributeError, NotImplementedError, OSError):
+        return default
+
+def collect_process_details(proc, full=True):
+    """Everything the process detail window shows, as plain data. Fields the user may not
+    read come back as ACCESS_DENIED. full=False skips the expensive parts (environment,
+    files, connections, threads, limits, memory maps)."""
+    with proc.oneshot():
+        d = {
+            "pid": proc.pid,
+            "name": _proc_call(proc.name),
+            "status": _proc_call(proc.status),
+            "username": _proc_call(proc.username),
+            "cmdline": _proc_call(proc.cmdline),
+            "exe": _proc_call(proc.exe),
+            "cwd": _proc_call(proc.cwd),
+            "create_time": _proc_call(proc.create_time),
+            "num_threads": _proc_call(proc.num_threads),
+            "memory_rss": _proc_call(lambda: proc.memory_info().rss),
+            "memory_vms": _proc_call(lambda: proc.memory_info().vms),
+            "memory_full": _proc_call(lambda: proc.memory_full_info()._asdict()),
+            "parents": _proc_call(lambda: [(p.pid, _proc_call(p.name)) for p in proc.parents()]),
+        }
+    if not full:
+        return d
+    connections = getattr(proc, "net_connections", None) or getattr(proc, "connections")
+    d["environ"] = _proc_call(proc.environ)
+    d["open_files"] = _proc_call(lambda: [(f.fd, f.path) for f in proc.open_files()])
+    d["connections"] = _proc_call(lambda: [{
+        "fd": c.fd,
+        "type": {socket.SOCK_STREAM: "TCP", socket.SOCK_DGRAM: "UDP"}.get(c.type, str(c.type)),
+        "laddr": ":".join(str(x) for x in c.laddr) if c.laddr else "",
+        "raddr": ":".join(str(x) for x in c.raddr) if c.raddr else "",
+        "status": c.status,
+    } for c in connections(kind="inet")])
+    d["threads"] = _proc_call(lambda: [(t.id, t.user_time, t.system_time) for t in proc.threads()])
+    d["rlimits"] = _proc_call(lambda: [(name[len("RLIMIT_"):], proc.rlimit(getattr(psutil, name)))
+                                       for name in sorted(dir(psutil)) if name.startswith("RLIMIT_")])
+    d["memory_maps"] = _proc_call(lambda: [m._asdict() for m in proc.memory_maps(grouped=True)])
+    return d
+
+def terminate_process(pid):
+    """Send SIGTERM (or the platform equivalent) to pid. Raises psutil errors on failure."""
+    psutil.Process(pid).terminate()
+
+# Signals offered in the process context menu, with the psutil call used for each where one
+# exists (those also work on Windows); the rest go through send_signal.
+SIGNAL_ACTIONS = [
+    ("SIGTERM", "Terminate", "terminate"),
+    ("SIGKILL", "Kill", "kill"),
+    ("SIGHUP", "Hang up", None),
+    ("SIGINT", "Interrupt", None),
+    ("SIGSTOP", "Suspend", "suspend"),
+    ("SIGCONT", "Resume", "resume"),
+]
+
+def available_signal_actions():
+    """SIGNAL_ACTIONS usable on this platform."""
+    return [(name, label, method) for name, label, method in SIGNAL_ACTIONS
+            if method or hasattr(signal, name)]
+
+def parse_signal(text):
+    """"SIGUSR1", "usr1" or "10" -> signal number. Raises ValueError for unknown signals."""
+    text = (text or "").strip().upper()
+    if text.isdigit():
+        return int(text)
+    name = text if text.startswith("SIG") else "SIG" + text
+    sig = getattr(signal, name, None)
+    if not isinstance(sig, int) or name.startswith("SIG_"):
+        raise ValueError(f"unknown signal {text!r}")
+    return int(sig)
+
+def send_process_signal(pid, sig):
+    """Send a signal, given by name (see SIGNAL_ACTIONS) or number, to pid."""
+    proc = psutil.Process(pid)
+    method = next((m for name, _, m in SIGNAL_ACTIONS if name == sig and m), None)
+    if method:
+        getattr(proc, method)()
+    else:
+        proc.send_signal(getattr(signal, sig) if isinstance(sig, str) else sig)
+
+def process_tree_kill_order(pid):
+    """pid and all its descendants, deepest first, so children go down before their parents."""
+    proc = psutil.Process(pid)
+    levels = {proc.pid: 0}
+    ordered = [proc]
+    for child in proc.children(recursive=True):
+        try:
+            levels[child.pid] = levels.get(child.ppid(), 0) + 1
+        except psutil.NoSuchProcess:
+            continue
+        ordered.append(child)
+    return sorted(ordered, key=lambda p: levels.get(p.pid, 0), reverse=True)
+
+def process_exited(proc):
+    """True once proc is gone or only a zombie waiting for its parent."""
+    try:
+        return not proc.is_running() or proc.status() == psutil.STATUS_ZOMBIE
+    except psutil.NoSuchProcess:
+        return True
+
+# I/O scheduling classes as (label, psutil constant, takes a 0-7 level). Linux has the
+# IOPRIO_CLASS_* ones, Windows the IOPRIO_* ones; only those present in psutil are offered.
+IONICE_CLASSES = [
+    ("none", "IOPRIO_CLASS_NONE", False),
+    ("realtime", "IOPRIO_CLASS_RT", True),
+    ("best-effort", "IOPRIO_CLASS_BE", True),
+    ("idle", "IOPRIO_CLASS_IDLE", False),
+    ("very low", "IOPRIO_VERYLOW", False),
+    ("low", "IOPRIO_LOW", False),
+    ("normal", "IOPRIO_NORMAL", False),
+    ("high", "IOPRIO_HIGH", False),
+]
+# Windows has priority classes instead of nice values
+PRIORITY_CLASSES = [
+    ("idle", "IDLE_PRIORITY_CLASS"),
+    ("below normal", "BELOW_NORMAL_PRIORITY_CLASS"),
+    ("normal", "NORMAL_PRIORITY_CLASS"),
+    ("above normal", "ABOVE_NORMAL_PRIORITY_CLASS"),
+    ("high", "HIGH_PRIORITY_CLASS"),
+    ("realtime", "REALTIME_PRIORITY_CLASS"),
+]
+# Shown with AccessDenied so unprivileged users know why a scheduling change failed
+SCHED_PERMISSION_HINTS = {
+    "nice": "Only root can lower a nice value (raise priority) or renice another user's process.",
+    "ionice": "Only root can use the realtime I/O class or change another user's I/O priority.",
+    "affinity": "Only root can change the CPU affinity of another user's process.",
+}
+
+def available_ionice_classes():
+    """IONICE_CLASSES usable on this platform, with the psutil constant resolved."""
+    return [(label, getattr(psutil, const), level) for label, const, level in IONICE_CLASSES if hasattr(psutil, const)]
+
+def available_priority_classes():
+    return [(label, getattr(psutil, const)) for label, const in PRIORITY_CLASSES if hasattr(psutil, const)]
+
+def ionice_label(value):
+    """psutil ionice() result -> "best-effort/4", "idle", "normal"; None when unknown."""
+    if value is None:
+        return None
+    ioclass, level = (value.ioclass, value.value) if hasattr(value, "ioclass") else (value, None)
+    for label, const, takes_level in available_ionice_classes():
+        if const == ioclass:
+            return f"{label}/{level}" if takes_level and level is not None else label
+    return str(int(ioclass))
+
+def nice_str(value):
+    """Nice value, or the priority class name on Windows."""
+    if value is None:
+        return "--"
+    if psutil.WINDOWS:
+        return next((label for label, const in available_priority_classes() if const == value), str(value))
+    return str(value)
+
+def affinity_str(cpus, count=None):
+    """[0,1,2,3,6] -> "0-3,6"; "all" when every CPU is allowed."""
+    if not cpus:
+        return "--"
+    cpus = sorted(cpus)
+    if count and cpus == list(range(count)):
+        return "all"
+    ranges = []
+    start = prev = cpus[0]
+    for c in cpus[1:] + [None]:
+        if c is not None and c == prev + 1:
+            prev = c
+            continue
+        ranges.append(str(start) if start == prev else f"{start}-{prev}")
+        start = prev = c
+    return ",".join(ranges)
+
+def set_process_nice(pid, value):
+    """Set pid's nice value (a priority class constant on Windows)."""
+    psutil.Process(pid).nice(value)
+
+def set_process_ionice(pid, ioclass, level=None):
+    """Set pid's I/O scheduling class, and its 0-7 level for classes that take one."""
+    proc = psutil.Process(pid)
+    if level is None:
+        proc.ionice(ioclass)
+    else:
+        proc.ionice(ioclass, level)
+
+def set_process_affinity(pid, cpus):
+    """Restrict pid to the given CPU numbers."""
+    psutil.Process(pid).cpu_affinity(sorted(cpus))
+
+def permission_hint(action):
+    """SCHED_PERMISSION_HINTS[action], naming Administrator instead of root on Windows."""
+    hint = SCHED_PERMISSION_HINTS[action]
+    return hint.replace("root", "Administrator") if psutil.WINDOWS else hint
+
+def scheduling_error_message(action, pid, name, e):
+    """User-facing text for a failed nice/ionice/affinity change."""
+    msg = f"Could not change {name} (PID {pid}): {e}"
+    if isinstance(e, (psutil.AccessDenied, PermissionError)):
+        msg += f"\n\nPermission denied. {permission_hint(action)}"
+    elif isinstance(e, psutil.NoSuchProcess):
+        msg = f"Process {name} (PID {pid}) no longer exists."
+    return msg
+
+def describe_targets(targets, limit=15):
+    """"name (PID n)" lines for a confirmation dialog, eliding all but the first limit."""
+    lines = [f"{name} (PID {pid})" for pid, name in targets[:limit]]
+    if len(targets) > limit:
+        lines.append(f"... and {len(targets) - limit} more")
+    return "\n".join(lines)
+
+def batch_summary(what, done, failed, sched=None):
+    """Result text for an action applied to several processes: done is [(pid, name)],
+    failed is [(pid, name, exception)]."""
+    lines = [f"{what[0].upper()}{what[1:]} {len(done)} of {len(done) + len(failed)} processes."]
+    if done:
+        lines += ["", "Succeeded:", describe_targets(done)]
+    if failed:
+        lines += ["", "Failed:"]
+        lines += [f"{name} (PID {pid}): " + ("no longer exists" if isinstance(e, psutil.NoSuchProcess) else str(e) or type(e).__name__)
+                  for pid, name, e in failed]
+        if sched and any(isinstance(e, (psutil.AccessDenied, PermissionError)) for _, _, e in failed):
+            lines += ["", permission_hint(sched)]
+    return "\n".join(lines)
+
 # -----------------------
 # GUI Application
 # -----------------------
 class VanillaLOOKApp:
-    def __init__(self, root):
+    def __init__(self, root, recorder=None, export_top_n=None, full_memory_top=FULL_MEMORY_TOP_N):
         self.root = root
         root.title(f"{APP_NAME} {APP_VERSION}")
-        self.sampler = SystemSampler()
+        self.sampler = SystemSampler(full_memory_top)
         self.updating = True
-        self.log_history = []
+        self.recorder = recorder or SessionRecorder(top_n=export_top_n)
+        self.export_top_n = export_top_n
         self.logging_enabled = False
-        self.log_lock = threading.Lock()
 
         self.cpu_history = []
         self.mem_history = []
         self.time_history = []
+        self.nic_history = {}  # interface name -> {"rx": [...], "tx": [...]} in bytes/s
+        self.blk_history = {}  # disk device -> {"read": [...], "write": [...], "util": [...]}
+        self.user_history = {}  # user -> {"cpu": [...], "rss": [...]}
+        self.cpu_count = psutil.cpu_count(logical=True) or 1
+        self.watchlist = [{"kind": "name", "pattern": p} for p in load_settings(WATCHLIST_PATH).get("patterns") or []][:WATCH_MAX]
+        self.watch_history = {}  # watch_key -> {metric: [...]} for WATCH_METRICS, None while nothing matches
+        self.event_log = []  # live process events, oldest first (EVENT_LIST_MAX)
+        self._leak_rows = set()  # proc_tree iids tagged as suspected leaks, groups with a leaking member included
+        self.shown_events = []  # what the Events tab shows: event_log, or a recording's events up to the replay position
+        self._tree_values = {}  # Treeview path -> {iid: values last shown}, see _sync_tree
+        saved = load_settings(COLUMNS_PATH)
+        self.column_settings = saved
+        self.proc_columns = saved_proc_columns(saved)
+        self._filter_text = ""  # process filter expression last compiled, see _filter_processes
+        self._filter_pred = None
+        self.table_sorts = {}  # Treeview path -> (column, descending) chosen by clicking a heading
+        self._headings = {}  # Treeview path -> {column: heading text without the sort arrow}
 
         self.queue = queue.Queue()
 
+        # replay state: while replay_snapshots is set, live snapshots are recorded and kept in
+        # live_history but not painted
+        self.replay_snapshots = None
+        self.live_history = {}
+        self.replay_index = 0
+        self.replay_playing = False
+        self.replay_job = None
+        self.replay_path = None
+
         self._create_widgets()
+        root.protocol("WM_DELETE_WINDOW", self.on_close)
         self._start_background_sampler()
         self._schedule_ui_update()
 
@@ -188,8 +1399,9 @@ class VanillaLOOKApp:
         file_menu = tk.Menu(menubar, tearoff=0)
         file_menu.add_command(label="Take Snapshot", command=self.take_snapshot)
         file_menu.add_command(label="Export Logs (JSON)", command=self.export_logs)
+        file_menu.add_command(label="Open Recording...", command=self.open_recording)
         file_menu.add_separator()
-        file_menu.add_command(label="Exit", command=self.root.quit)
+        file_menu.add_command(label="Exit", command=self.on_close)
         menubar.add_cascade(label="File", menu=file_menu)
 
         # Help menu
@@ -200,6 +1412,7 @@ class VanillaLOOKApp:
         # Main layout
         main = ttk.Frame(self.root, padding=(8,8))
         main.pack(fill=tk.BOTH, expand=True)
+        self.main_frame = main
 
         left = ttk.Frame(main)
         right = ttk.Frame(main)
@@ -242,38 +1455,151 @@ class VanillaLOOKApp:
         # Disk tab
         disk_tab = ttk.Frame(nb)
         nb.add(disk_tab, text="Disk")
-        self.disk_tree = ttk.Treeview(disk_tab, columns=("mount","fstype","total","used","free","percent"), show='headings')
-        for col, txt in [("mount","Mountpoint"),("fstype","FS"),("total","Total"),("used","Used"),("free","Free"),("percent","%")]:
-            self.disk_tree.heading(col, text=txt)
+        self.disk_tree = ttk.Treeview(disk_tab, columns=("mount","device","fstype","total","used","free","percent"), show='headings', height=6)
+        self._make_sortable(self.disk_tree, [("mount","Mountpoint"),("device","Device"),("fstype","FS"),("total","Total"),
+                                             ("used","Used"),("free","Free"),("percent","%")])
+        for col in self.disk_tree["columns"]:
             self.disk_tree.column(col, anchor=tk.W, width=100)
         self.disk_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        blk_cols = [("name","Device"),("mounts","Mounted"),("read","Read/s"),("write","Write/s"),("riops","Read IOPS"),
+                    ("wiops","Write IOPS"),("rlat","Read lat"),("wlat","Write lat"),("util","Util %")]
+        self.blk_tree = ttk.Treeview(disk_tab, columns=[c for c, _ in blk_cols], show='headings', height=5)
+        self._make_sortable(self.blk_tree, blk_cols)
+        for col, _ in blk_cols:
+            self.blk_tree.column(col, anchor=tk.W, width=75)
+        self.blk_tree.column("mounts", width=140)
+        self.blk_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.blk_tree.bind("<<TreeviewSelect>>", lambda e: self._update_blk_chart())
+        self.blk_fig = Figure(figsize=(5,2), dpi=80)
+        self.blk_ax = self.blk_fig.add_subplot(111)
+        self.blk_util_ax = self.blk_ax.twinx()
+        self.blk_canvas = FigureCanvasTkAgg(self.blk_fig, master=disk_tab)
+        self.blk_canvas.get_tk_widget().pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
 
         # Network tab
         net_tab = ttk.Frame(nb)
         nb.add(net_tab, text="Network")
-        self.net_text = tk.Text(net_tab, height=8, wrap=tk.NONE)
-        self.net_text.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.net_text = tk.Text(net_tab, height=7, wrap=tk.NONE)
+        self.net_text.pack(fill=tk.X, padx=4, pady=4)
+        nic_cols = [("name","Interface"),("state","State"),("speed","Speed"),("mtu","MTU"),("duplex","Duplex"),
+                    ("addr","Addresses"),("rx_rate","Recv/s"),("tx_rate","Sent/s"),("rx","Recv"),("tx","Sent"),
+                    ("errors","Errors"),("drops","Drops")]
+        self.nic_tree = ttk.Treeview(net_tab, columns=[c for c, _ in nic_cols], show='headings', height=6)
+        self._make_sortable(self.nic_tree, nic_cols)
+        for col, _ in nic_cols:
+            self.nic_tree.column(col, anchor=tk.W, width=70)
+        self.nic_tree.column("addr", width=160)
+        self.nic_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.nic_tree.bind("<<TreeviewSelect>>", lambda e: self._update_nic_chart())
+        self.nic_fig = Figure(figsize=(5,2), dpi=80)
+        self.nic_ax = self.nic_fig.add_subplot(111)
+        self.nic_canvas = FigureCanvasTkAgg(self.nic_fig, master=net_tab)
+        self.nic_canvas.get_tk_widget().pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
 
         # Processes tab
         proc_tab = ttk.Frame(nb)
         nb.add(proc_tab, text="Processes")
         proc_ctrl = ttk.Frame(proc_tab)
         proc_ctrl.pack(fill=tk.X, padx=4, pady=2)
-        ttk.Label(proc_ctrl, text="Search:").pack(side=tk.LEFT)
-        self.proc_search = ttk.Entry(proc_ctrl)
+        ttk.Label(proc_ctrl, text="Filter:").pack(side=tk.LEFT)
+        self.proc_search = ttk.Entry(proc_ctrl, width=30)
         self.proc_search.pack(side=tk.LEFT, padx=4)
         self.proc_search.bind("<Return>", lambda e: self.refresh_processes())
+        self.saved_filters = load_settings(SAVED_FILTERS_PATH)
+        self.filter_choice = ttk.Combobox(proc_ctrl, values=sorted(self.saved_filters), state="readonly", width=12)
+        self.filter_choice.pack(side=tk.LEFT)
+        self.filter_choice.bind("<<ComboboxSelected>>", lambda e: self._apply_saved_filter())
+        ttk.Button(proc_ctrl, text="Save", width=5, command=self.save_current_filter).pack(side=tk.LEFT, padx=(2,0))
+        ttk.Button(proc_ctrl, text="Delete", width=6, command=self.delete_saved_filter).pack(side=tk.LEFT, padx=2)
         ttk.Button(proc_ctrl, text="Refresh", command=self.refresh_processes).pack(side=tk.LEFT, padx=4)
+        self.freeze_procs = tk.BooleanVar(value=False)
+        ttk.Checkbutton(proc_ctrl, text="Freeze updates", variable=self.freeze_procs,
+                        command=self._toggle_freeze).pack(side=tk.LEFT, padx=4)
         ttk.Label(proc_ctrl, text="Sort by:").pack(side=tk.LEFT, padx=(8,2))
-        self.sort_by = ttk.Combobox(proc_ctrl, values=["cpu","memory"], state="readonly", width=8)
+        self.sort_by = ttk.Combobox(proc_ctrl, values=SORT_KEYS, state="readonly", width=8)
         self.sort_by.set("cpu")
         self.sort_by.pack(side=tk.LEFT)
+        self.sort_by.bind("<<ComboboxSelected>>", lambda e: self._clear_heading_sort(self.proc_tree))
+        self.tree_mode = tk.BooleanVar(value=False)
+        ttk.Checkbutton(proc_ctrl, text="Tree view", variable=self.tree_mode,
+                        command=lambda: self._set_view_mode(self.tree_mode)).pack(side=tk.LEFT, padx=(8,0))
+        self.group_mode = tk.BooleanVar(value=False)
+        ttk.Checkbutton(proc_ctrl, text="Group by app", variable=self.group_mode,
+                        command=lambda: self._set_view_mode(self.group_mode)).pack(side=tk.LEFT, padx=8)
+        ttk.Button(proc_ctrl, text="Columns...", command=self.choose_columns).pack(side=tk.LEFT)
         ttk.Button(proc_ctrl, text="Kill Selected", command=self.kill_selected_process).pack(side=tk.RIGHT, padx=4)
-        self.proc_tree = ttk.Treeview(proc_tab, columns=("pid","name","user","cpu","mem","rss","status"), show='headings')
-        for col, txt in [("pid","PID"),("name","Name"),("user","User"),("cpu","CPU%"),("mem","Mem%"),("rss","RSS"),("status","Status")]:
-            self.proc_tree.heading(col, text=txt)
-            self.proc_tree.column(col, anchor=tk.W, width=80)
+        self.proc_tree = ttk.Treeview(proc_tab, columns=PROC_COLUMNS + EXTRA_COLUMNS + TREE_COLUMNS, show='headings', selectmode='extended')
+        self._make_sortable(self.proc_tree, PROC_HEADINGS)
+        widths = self.column_settings.get("widths") or {}
+        for col in self.proc_tree["columns"]:
+            self.proc_tree.column(col, anchor=tk.W, width=widths.get(col, 80))
+        self._apply_proc_columns()
+        self.filter_error = ttk.Label(proc_tab, foreground="red")  # packed only while the filter doesn't parse
+        self.proc_tree.tag_configure("leak", foreground="#c05000")
+        self.proc_tree.heading("#0", text="Process")
+        self.proc_tree.column("#0", width=220, stretch=False)
         self.proc_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.proc_tree.bind("<Double-1>", self._on_proc_double_click)
+        self.proc_menu = tk.Menu(self.root, tearoff=0)
+        self.proc_menu.add_command(label="Details...", command=lambda: self._with_selected(self.show_process_details))
+        self.proc_menu.add_command(label="Export Memory History...", command=self.export_memory_history)
+        self.proc_menu.add_command(label="Pin to Watchlist", command=self.pin_selected_processes)
+        self.proc_menu.add_separator()
+        for sig, label, _ in available_signal_actions():
+            self.proc_menu.add_command(label=f"{label} ({sig})", command=lambda s=sig: self.signal_selected_process(s))
+        self.proc_menu.add_command(label="Send Signal...", command=self.signal_selected_process_prompt)
+        self.proc_menu.add_separator()
+        self.proc_menu.add_command(label="Terminate, Then Kill After...", command=self.escalate_selected_process)
+        self.proc_menu.add_command(label="Kill Whole Tree (Children First)", command=self.kill_selected_tree)
+        self.proc_menu.add_separator()
+        self.proc_menu.add_command(label="Set Priority (Nice)...", command=self.renice_selected_process)
+        if available_ionice_classes():
+            self.proc_menu.add_command(label="Set I/O Priority...", command=self.ionice_selected_process)
+        if hasattr(psutil.Process, "cpu_affinity"):
+            self.proc_menu.add_command(label="Set CPU Affinity...", command=self.affinity_selected_process)
+        # Button-2 is the right button on macOS
+        for button in ("<Button-3>", "<Button-2>"):
+            self.proc_tree.bind(button, self._on_proc_context_menu)
+
+        # Users tab: per-user totals, their history and logged-in sessions
+        users_tab = ttk.Frame(nb)
+        nb.add(users_tab, text="Users")
+        user_cols = [("user","User"),("procs","Processes"),("cpu","CPU%"),("mem","Mem%"),("rss","RSS"),
+                     ("read","Read/s"),("write","Write/s"),("sessions","Sessions")]
+        self.user_tree = ttk.Treeview(users_tab, columns=[c for c, _ in user_cols], show='headings', height=6)
+        self._make_sortable(self.user_tree, user_cols)
+        for col, _ in user_cols:
+            self.user_tree.column(col, anchor=tk.W, width=80)
+        self.user_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.user_tree.bind("<<TreeviewSelect>>", lambda e: self._update_user_chart())
+        session_cols = [("name","User"),("terminal","Terminal"),("host","Host"),("started","Login Time"),("pid","PID")]
+        self.session_tree = ttk.Treeview(users_tab, columns=[c for c, _ in session_cols], show='headings', height=4)
+        self._make_sortable(self.session_tree, session_cols)
+        for col, _ in session_cols:
+            self.session_tree.column(col, anchor=tk.W, width=110)
+        self.session_tree.pack(fill=tk.X, padx=4, pady=4)
+        self.user_fig = Figure(figsize=(5,2), dpi=80)
+        self.user_cpu_ax = self.user_fig.add_subplot(121)
+        self.user_mem_ax = self.user_fig.add_subplot(122)
+        self.user_canvas = FigureCanvasTkAgg(self.user_fig, master=users_tab)
+        self.user_canvas.get_tk_widget().pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+
+        # Watchlist tab: pinned processes with their own history
+        self.watch_tab = ttk.Frame(nb)
+        nb.add(self.watch_tab, text="Watchlist")
+        w_ctrl = ttk.Frame(self.watch_tab)
+        w_ctrl.pack(fill=tk.X, padx=4, pady=4)
+        ttk.Button(w_ctrl, text="Pin Name Pattern...", command=self.pin_name_pattern).pack(side=tk.LEFT)
+        ttk.Button(w_ctrl, text="Unpin", command=self.unpin_selected).pack(side=tk.LEFT, padx=4)
+        ttk.Label(w_ctrl, text="Pin single processes from the Processes tab's right-click menu.").pack(side=tk.LEFT, padx=8)
+        w_body = ttk.Frame(self.watch_tab)
+        w_body.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        self.watch_listbox = tk.Listbox(w_body, width=40, selectmode=tk.EXTENDED, exportselection=False)
+        self.watch_listbox.pack(side=tk.LEFT, fill=tk.Y)
+        self.watch_fig = Figure(figsize=(5,3), dpi=80)
+        self.watch_canvas = FigureCanvasTkAgg(self.watch_fig, master=w_body)
+        self.watch_canvas.get_tk_widget().pack(side=tk.LEFT, fill=tk.BOTH, expand=True, padx=(4,0))
+        self.watch_tab.bind("<Map>", lambda e: self._update_watch_chart())
 
         # Logs tab
         log_tab = ttk.Frame(nb)
@@ -287,10 +1613,44 @@ class VanillaLOOKApp:
         self.log_text = tk.Text(log_tab, height=12, wrap=tk.NONE)
         self.log_text.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
 
+        # Events tab: processes started/exited, newest first
+        events_tab = ttk.Frame(nb)
+        nb.add(events_tab, text="Events")
+        ev_ctrl = ttk.Frame(events_tab)
+        ev_ctrl.pack(fill=tk.X, padx=4, pady=4)
+        ttk.Button(ev_ctrl, text="Clear Events", command=self.clear_events).pack(side=tk.LEFT)
+        self.event_stats_var = tk.StringVar(value="")
+        ttk.Label(ev_ctrl, textvariable=self.event_stats_var).pack(side=tk.LEFT, padx=8)
+        ev_cols = [("time","Time"),("event","Event"),("pid","PID"),("name","Name"),("lifetime","Lifetime"),
+                   ("peak","Peak RSS"),("cpu","CPU Time"),("cmdline","Command Line")]
+        self.event_tree = ttk.Treeview(events_tab, columns=[c for c, _ in ev_cols], show='headings')
+        self._make_sortable(self.event_tree, ev_cols)
+        for col, _ in ev_cols:
+            self.event_tree.column(col, anchor=tk.W, width=80)
+        self.event_tree.column("time", width=140)
+        self.event_tree.column("name", width=140)
+        self.event_tree.column("cmdline", width=320)
+        self.event_tree.tag_configure("exited", foreground="gray40")
+        self.event_tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+
         self.status_var = tk.StringVar(value="Ready")
         statusbar = ttk.Label(self.root, textvariable=self.status_var, relief=tk.SUNKEN, anchor=tk.W)
         statusbar.pack(side=tk.BOTTOM, fill=tk.X)
 
+        # Replay controls, only shown while a recording is open
+        self.replay_bar = ttk.Frame(self.root, padding=(8,2))
+        self.replay_play_btn = ttk.Button(self.replay_bar, text="Play", width=6, command=self.toggle_replay_play)
+        self.replay_play_btn.pack(side=tk.LEFT, padx=2)
+        ttk.Label(self.replay_bar, text="Speed:").pack(side=tk.LEFT, padx=(8,2))
+        self.replay_speed = ttk.Combobox(self.replay_bar, values=REPLAY_SPEEDS, state="readonly", width=6)
+        self.replay_speed.set("1x")
+        self.replay_speed.pack(side=tk.LEFT)
+        self.replay_scale = ttk.Scale(self.replay_bar, from_=0, to=0, orient=tk.HORIZONTAL, command=self._on_replay_scrub)
+        self.replay_scale.pack(side=tk.LEFT, fill=tk.X, expand=True, padx=8)
+        self.replay_pos_var = tk.StringVar(value="")
+        ttk.Label(self.replay_bar, textvariable=self.replay_pos_var, width=30).pack(side=tk.LEFT)
+        ttk.Button(self.replay_bar, text="Back to Live", command=self.close_recording).pack(side=tk.LEFT, padx=2)
+
     # ---------------- About dialog ----------------
     def show_about(self):
         about_window = Toplevel(self.root)
@@ -305,15 +1665,7 @@ class VanillaLOOKApp:
 
     # ---------------- background sampling ----------------
     def _start_background_sampler(self):
-        def sampler_thread():
-            while True:
-                try:
-                    snap = self.sampler.snapshot()
-                    self.queue.put(snap)
-                except Exception as e:
-                    self.queue.put({"timestamp": now_iso(), "error": str(e)})
-                time.sleep(1.0)
-        threading.Thread(target=sampler_thread, daemon=True).start()
+        start_sampler_thread(self.sampler, self.queue)
 
     def _schedule_ui_update(self):
         self._process_queue()
@@ -323,57 +1675,91 @@ class VanillaLOOKApp:
         processed = False
         while not self.queue.empty():
             snap = self.queue.get_nowait()
+            if self.replay_snapshots is not None:
+                # the UI is showing a recording: keep collecting live data off screen
+                shown = self._swap_history(self.live_history)
+                self._record_snapshot(snap)
+                self.live_history = self._swap_history(shown)
+                continue
             self._apply_snapshot(snap)
             processed = True
         if not processed:
             pass
 
     # ---------------- snapshot handling ----------------
-    def _apply_snapshot(self, s):
+    def _apply_snapshot(self, s, live=True):
+        """Show a snapshot in every panel. Replayed snapshots (live=False) don't touch the
+        chart history or the recorder; the replay code sets the history window itself."""
         if "error" in s:
             self.status_var.set("Sampler error: " + s["error"])
             return
+        if live and not self._record_snapshot(s):
+            return
         cpu_total = s['cpu']['total_percent']
         mem_percent = s['memory']['virtual']['percent']
         self.cpu_label.config(text=f"CPU: {cpu_total:.1f}% ({len(s['cpu']['per_core'])} cores)")
         self.mem_label.config(text=f"Memory: {mem_percent:.1f}% ({human_bytes(s['memory']['virtual']['used'])} used)")
-        ts = time.time()
-        self.time_history.append(ts)
-        self.cpu_history.append(cpu_total)
-        self.mem_history.append(mem_percent)
-        if len(self.time_history) > CHART_POINTS:
-            self.time_history = self.time_history[-CHART_POINTS:]
-            self.cpu_history = self.cpu_history[-CHART_POINTS:]
-            self.mem_history = self.mem_history[-CHART_POINTS:]
         self._update_charts()
-        for i in self.disk_tree.get_children():
-            self.disk_tree.delete(i)
-        for p in s['disk']['partitions']:
-            usage = p.get('usage')
-            self.disk_tree.insert("", tk.END, values=(
-                p.get('mountpoint'),
-                p.get('fstype'),
-                human_bytes(usage.get('total')) if usage else "N/A",
-                human_bytes(usage.get('used')) if usage else "N/A",
-                human_bytes(usage.get('free')) if usage else "N/A",
-                f"{usage.get('percent')}%" if usage else "N/A"
-            ))
+        self._refresh_disk_tree(s['disk']['partitions'])
+        self._refresh_blk_tree(s['disk'].get('devices') or [])
+        self._update_blk_chart()
         net = s['network']
         self.net_text.delete(1.0, tk.END)
         io = net['io']
         rates = net.get('rates') or {}
         self.net_text.insert(tk.END, f"Timestamp: {s['timestamp']}\n")
-        self.net_text.insert(tk.END, f"Bytes Sent: {human_bytes(io['bytes_sent'])} (≈ {human_bytes(rates.get('bytes_sent_per_sec') or 0)}/s)\n")
-        self.net_text.insert(tk.END, f"Bytes Recv: {human_bytes(io['bytes_recv'])} (≈ {human_bytes(rates.get('bytes_recv_per_sec') or 0)}/s)\n")
-        self.net_text.insert(tk.END, f"Packets Sent: {io.get('packets_sent')}\n")
-        self.net_text.insert(tk.END, f"Packets Recv: {io.get('packets_recv')}\n")
+        for line in network_lines(io, rates):
+            self.net_text.insert(tk.END, line + "\n")
+        self._refresh_nic_tree(net.get('interfaces') or [])
+        self._update_nic_chart()
+        self._refresh_user_tables(s)
+        self._update_user_chart()
+        self._update_watchlist()
+        if live:
+            self._add_events(s.get('events') or [])
         self.latest_snapshot = s
-        self.refresh_processes(use_latest=True)
+        if not self.freeze_procs.get():
+            self.refresh_processes()
+        if live:
+            self.status_var.set(f"Last update: {s['timestamp']}")
+
+    def _record_snapshot(self, s):
+        """Book-keeping for a live snapshot that doesn't paint anything: chart history, the
+        event log and the recorder. Returns False if recording failed and was stopped."""
+        if "error" in s:
+            return False
+        cpu_total = s['cpu']['total_percent']
+        mem_percent = s['memory']['virtual']['percent']
+        self.time_history.append(time.time())
+        self.cpu_history.append(cpu_total)
+        self.mem_history.append(mem_percent)
+        if len(self.time_history) > CHART_POINTS:
+            self.time_history = self.time_history[-CHART_POINTS:]
+            self.cpu_history = self.cpu_history[-CHART_POINTS:]
+            self.mem_history = self.mem_history[-CHART_POINTS:]
+        self._push_nic_history(s)
+        self._push_blk_history(s)
+        self._push_watch_history(s)
+        self._push_user_history(s)
+        self.event_log = (self.event_log + (s.get('events') or []))[-EVENT_LIST_MAX:]
         if self.logging_enabled:
-            with self.log_lock:
-                self.log_history.append(s)
-                self.log_listbox.insert(tk.END, f"{s['timestamp']}  CPU {cpu_total:.1f}%  MEM {mem_percent:.1f}%")
-        self.status_var.set(f"Last update: {s['timestamp']}")
+            try:
+                self.recorder.write(s)
+            except OSError as e:
+                self.toggle_logging()
+                messagebox.showerror(APP_NAME, f"Recording stopped: {e}")
+                return False
+            self.log_listbox.insert(tk.END, f"{s['timestamp']}  CPU {cpu_total:.1f}%  MEM {mem_percent:.1f}%")
+            if self.log_listbox.size() > LOG_LIST_MAX:
+                self.log_listbox.delete(0, self.log_listbox.size() - LOG_LIST_MAX - 1)
+        return True
+
+    def _swap_history(self, saved):
+        """Put the chart history in `saved` (attribute -> value) in place and return what it replaced."""
+        current = {a: getattr(self, a) for a in HISTORY_ATTRS}
+        for a, v in saved.items():
+            setattr(self, a, v)
+        return current
 
     def _update_charts(self):
         self.cpu_ax.clear()
@@ -390,6 +1776,173 @@ class VanillaLOOKApp:
             self.cpu_ax.text(0.5, 0.5, "No data yet", ha="center", va="center")
         self.canvas.draw_idle()
 
+    # ---------------- network interfaces ----------------
+    def _push_nic_history(self, s):
+        interfaces = s['network'].get('interfaces') or []
+        for n in interfaces:
+            h = self.nic_history.setdefault(n['name'], {"rx": [], "tx": []})
+            rates = n.get('rates') or {}
+            h["rx"] = (h["rx"] + [rates.get('bytes_recv_per_sec') or 0.0])[-CHART_POINTS:]
+            h["tx"] = (h["tx"] + [rates.get('bytes_sent_per_sec') or 0.0])[-CHART_POINTS:]
+        present = {n['name'] for n in interfaces}
+        for name in [k for k in self.nic_history if k not in present]:
+            del self.nic_history[name]
+
+    def _refresh_nic_tree(self, interfaces):
+        rows = []
+        for n in sorted(interfaces, key=lambda n: n['name']):
+            io = n.get('io') or {}
+            rates = n.get('rates') or {}
+            speed = n.get('speed_mbps')
+            rows.append((n['name'], "", "", (
+                n['name'],
+                "up" if n.get('isup') else ("down" if n.get('isup') is not None else "?"),
+                f"{speed} Mb/s" if speed else "N/A",
+                n.get('mtu') or "N/A",
+                n.get('duplex') or "N/A",
+                ", ".join(a['address'] for a in n.get('addresses') or [] if a['family'] != "MAC") or "-",
+                f"{human_bytes(rates.get('bytes_recv_per_sec') or 0)}/s",
+                f"{human_bytes(rates.get('bytes_sent_per_sec') or 0)}/s",
+                human_bytes(io.get('bytes_recv')),
+                human_bytes(io.get('bytes_sent')),
+                (io.get('errin') or 0) + (io.get('errout') or 0),
+                (io.get('dropin') or 0) + (io.get('dropout') or 0),
+            )))
+        self._sync_tree(self.nic_tree, rows)
+
+    def _update_nic_chart(self):
+        self.nic_ax.clear()
+        sel = [iid for iid in self.nic_tree.selection() if iid in self.nic_history]
+        if sel:
+            name = sel[0]
+        elif self.nic_history:
+            # nothing selected: show the busiest interface
+            name = max(self.nic_history, key=lambda k: sum(self.nic_history[k]["rx"][-5:]) + sum(self.nic_history[k]["tx"][-5:]))
+        else:
+            name = None
+        if name:
+            h = self.nic_history[name]
+            x = list(range(len(h["rx"])))
+            self.nic_ax.plot(x, [v / 1024.0 for v in h["rx"]], label="Recv KB/s")
+            self.nic_ax.plot(x, [v / 1024.0 for v in h["tx"]], label="Sent KB/s", color="green")
+            self.nic_ax.set_title(f"{name} throughput", fontsize=9)
+            self.nic_ax.set_ylim(bottom=0)
+            self.nic_ax.legend(fontsize=8)
+        else:
+            self.nic_ax.text(0.5, 0.5, "No data yet", ha="center", va="center")
+        self.nic_canvas.draw_idle()
+
+    # ---------------- disk devices ----------------
+    def _push_blk_history(self, s):
+        devices = s['disk'].get('devices') or []
+        for d in devices:
+            h = self.blk_history.setdefault(d['name'], {"read": [], "write": [], "util": []})
+            rates = d.get('rates') or {}
+            h["read"] = (h["read"] + [rates.get('read_bytes_per_sec') or 0.0])[-CHART_POINTS:]
+            h["write"] = (h["write"] + [rates.get('write_bytes_per_sec') or 0.0])[-CHART_POINTS:]
+            h["util"] = (h["util"] + [d.get('util_percent') or 0.0])[-CHART_POINTS:]
+        present = {d['name'] for d in devices}
+        for name in [k for k in self.blk_history if k not in present]:
+            del self.blk_history[name]
+
+    def _refresh_disk_tree(self, partitions):
+        self._sync_tree(self.disk_tree, [(p.get('mountpoint'), "", "", (
+            p.get('mountpoint'),
+            p.get('io_device') or "-",
+            p.get('fstype'),
+            human_bytes(usage.get('total')) if usage else "N/A",
+            human_bytes(usage.get('used')) if usage else "N/A",
+            human_bytes(usage.get('free')) if usage else "N/A",
+            f"{usage.get('percent')}%" if usage else "N/A"
+        )) for p, usage in ((p, p.get('usage')) for p in partitions)])
+
+    def _refresh_blk_tree(self, devices):
+        rows = []
+        for d in devices:
+            rates = d.get('rates') or {}
+            rows.append((d['name'], "", "", (
+                d['name'],
+                ", ".join(d.get('partitions') or []) or "-",
+                f"{human_bytes(rates.get('read_bytes_per_sec') or 0)}/s",
+                f"{human_bytes(rates.get('write_bytes_per_sec') or 0)}/s",
+                rate_str(d.get('read_iops')),
+                rate_str(d.get('write_iops')),
+                rate_str(d.get('read_latency_ms'), "{:.2f} ms"),
+                rate_str(d.get('write_latency_ms'), "{:.2f} ms"),
+                rate_str(d.get('util_percent'), "{:.1f}%"),
+            )))
+        self._sync_tree(self.blk_tree, rows)
+
+    def _update_blk_chart(self):
+        self.blk_ax.clear()
+        self.blk_util_ax.clear()
+        sel = [iid for iid in self.blk_tree.selection() if iid in self.blk_history]
+        if sel:
+            name = sel[0]
+        elif self.blk_history:
+            name = max(self.blk_history, key=lambda k: sum(self.blk_history[k]["read"][-5:]) + sum(self.blk_history[k]["write"][-5:]))
+        else:
+            name = None
+        if name:
+            h = self.blk_history[name]
+            x = list(range(len(h["read"])))
+            self.blk_ax.plot(x, [v / 1024.0 for v in h["read"]], label="Read KB/s")
+            self.blk_ax.plot(x, [v / 1024.0 for v in h["write"]], label="Write KB/s", color="red")
+            self.blk_util_ax.plot(x, h["util"], label="Util %", color="gray", linestyle="--")
+            self.blk_util_ax.set_ylim(0, 100)
+            self.blk_ax.set_ylim(bottom=0)
+            self.blk_ax.set_title(f"{name} I/O", fontsize=9)
+            self.blk_ax.legend(fontsize=8, loc="upper left")
+            self.blk_util_ax.legend(fontsize=8, loc="upper right")
+        else:
+            self.blk_ax.text(0.5, 0.5, "No data yet", ha="center", va="center")
+        self.blk_canvas.draw_idle()
+
+    # ---------------- users ----------------
+    def _push_user_history(self, s):
+        totals = user_totals(s.get('processes') or [])
+        for user, t in totals.items():
+            h = self.user_history.setdefault(user, {"cpu": [], "rss": []})
+            h["cpu"] = (h["cpu"] + [t["cpu"]])[-CHART_POINTS:]
+            h["rss"] = (h["rss"] + [t["rss"]])[-CHART_POINTS:]
+        for user in [u for u in self.user_history if u not in totals]:
+            del self.user_history[user]
+
+    def _refresh_user_tables(self, s):
+        sessions = s.get('users') or []
+        count = {}
+        for u in sessions:
+            count[u['name']] = count.get(u['name'], 0) + 1
+        self._sync_tree(self.user_tree, [(user, "", "", (
+            user, t["procs"], f"{t['cpu']:.1f}", f"{t['mem']:.1f}", human_bytes(t["rss"]),
+            io_rate_str(t["read"]), io_rate_str(t["write"]), count.get(user, 0)
+        )) for user, t in sorted(user_totals(s.get('processes') or []).items(), key=lambda ut: -ut[1]["cpu"])])
+        self._sync_tree(self.session_tree, [(f"{i}", "", "", (
+            u['name'], u.get('terminal') or "-", u.get('host') or "-",
+            format_start_time(u.get('started')), u.get('pid') or "-"
+        )) for i, u in enumerate(sessions)])
+
+    def _update_user_chart(self):
+        """CPU and RSS over time for the selected users, or the five busiest ones."""
+        self.user_cpu_ax.clear()
+        self.user_mem_ax.clear()
+        users = [iid for iid in self.user_tree.selection() if iid in self.user_history]
+        if not users:
+            users = sorted(self.user_history, key=lambda u: -sum(self.user_history[u]["cpu"][-5:]))[:5]
+        for user in users:
+            h = self.user_history[user]
+            self.user_cpu_ax.plot(range(len(h["cpu"])), h["cpu"], label=user)
+            self.user_mem_ax.plot(range(len(h["rss"])), [v / (1024 * 1024) for v in h["rss"]], label=user)
+        if users:
+            self.user_cpu_ax.set_title("CPU % by user", fontsize=9)
+            self.user_mem_ax.set_title("RSS MB by user", fontsize=9)
+            self.user_cpu_ax.set_ylim(bottom=0)
+            self.user_mem_ax.set_ylim(bottom=0)
+            self.user_cpu_ax.legend(fontsize=7, loc="upper left")
+        else:
+            self.user_cpu_ax.text(0.5, 0.5, "No data yet", ha="center", va="center")
+        self.user_canvas.draw_idle()
+
     # ---------------- user actions ----------------
     def take_snapshot(self):
         s = getattr(self, "latest_snapshot", None)
@@ -398,73 +1951,1567 @@ class VanillaLOOKApp:
             return
         filename = f"snapshot_{int(time.time())}.json"
         with open(filename, "w") as f:
-            json.dump(s, f, indent=2)
+            json.dump(trim_processes(s, self.export_top_n), f, indent=2)
         messagebox.showinfo(APP_NAME, f"Snapshot saved to {filename}")
         self.status_var.set(f"Snapshot saved: {filename}")
 
     def toggle_logging(self):
+        if not self.logging_enabled:
+            try:
+                self.recorder.start()
+            except OSError as e:
+                messagebox.showerror(APP_NAME, f"Cannot start recording: {e}")
+                return
+        else:
+            self.recorder.stop()
         self.logging_enabled = not self.logging_enabled
+        self._update_full_memory()
         self.toggle_logging_btn.config(text="Stop Logging" if self.logging_enabled else "Start Logging")
-        self.status_var.set("Logging started" if self.logging_enabled else "Logging stopped")
+        self.status_var.set(f"Logging to {self.recorder.path}" if self.logging_enabled else "Logging stopped")
 
     def export_logs(self):
-        if not self.log_history:
+        if not self.recorder.count:
             messagebox.showwarning(APP_NAME, "No logs to export.")
             return
         fname = filedialog.asksaveasfilename(defaultextension=".json", filetypes=[("JSON Files","*.json")])
         if not fname:
             return
+        # stream the session back out as a JSON array without loading it all into memory
         with open(fname, "w") as f:
-            json.dump(self.log_history, f, indent=2)
-        messagebox.showinfo(APP_NAME, f"Logs exported to {fname}")
+            f.write("[\n")
+            for i, snap in enumerate(self.recorder.snapshots()):
+                if i:
+                    f.write(",\n")
+                f.write(json.dumps(snap, indent=2))
+            f.write("\n]\n")
+        missing = self.recorder.missing_parts()
+        if missing:
+            messagebox.showwarning(APP_NAME, f"Logs exported to {fname}, but {len(missing)} part(s) of this session "
+                                             f"are no longer on disk and are missing from the export:\n"
+                                             + "\n".join(os.path.basename(p) for p in missing))
+        else:
+            messagebox.showinfo(APP_NAME, f"Logs exported to {fname}")
         self.status_var.set(f"Logs exported: {fname}")
 
     def clear_logs(self):
-        self.log_history.clear()
+        # session files stay on disk; a new session starts so the next export begins here
+        if self.logging_enabled:
+            self.recorder.start()
+        else:
+            self.recorder.reset()
         self.log_listbox.delete(0, tk.END)
         self.log_text.delete(1.0, tk.END)
         self.status_var.set("Logs cleared")
 
-    def refresh_processes(self, use_latest=False):
-        if use_latest and hasattr(self, "latest_snapshot"):
-            snap = self.latest_snapshot
-            plist = snap['processes']
+    # ---------------- watchlist ----------------
+    def _push_watch_history(self, s):
+        plist = s.get('processes') or []
+        for entry in self.watchlist:
+            sample = watch_sample(entry, plist) or {}
+            h = self.watch_history.setdefault(watch_key(entry), {m: [] for m, _ in WATCH_METRICS})
+            for m, _ in WATCH_METRICS:
+                h[m] = (h[m] + [sample.get(m)])[-CHART_POINTS:]
+
+    def _update_watchlist(self):
+        """Refresh the entry list (with current values) and, if the tab is showing, the sparklines."""
+        plist = (getattr(self, "latest_snapshot", None) or {}).get('processes') or []
+        sel = self.watch_listbox.curselection()
+        self.watch_listbox.delete(0, tk.END)
+        for entry in self.watchlist:
+            sample = watch_sample(entry, plist)
+            if sample is None:
+                status = "exited" if entry["kind"] == "pid" else "no match"
+            else:
+                status = f"CPU {sample['cpu']:.1f}%  RSS {human_bytes(sample['rss'])}"
+                if entry["kind"] == "name":
+                    status += f"  ×{sample['count']}"
+            self.watch_listbox.insert(tk.END, f"{watch_label(entry)}  —  {status}")
+        for i in sel:
+            if i < len(self.watchlist):
+                self.watch_listbox.selection_set(i)
+        self._update_watch_chart()
+
+    def _update_watch_chart(self):
+        if not self.watch_tab.winfo_ismapped():
+            return  # drawing dozens of sparklines every second is wasted on a hidden tab
+        self.watch_fig.clear()
+        if not self.watchlist:
+            ax = self.watch_fig.add_subplot(111)
+            ax.axis("off")
+            ax.text(0.5, 0.5, "Nothing pinned yet", ha="center", va="center")
+        scale = {"rss": 1 / (1024 * 1024), "io": 1 / 1024}
+        rows = len(self.watchlist)
+        for i, entry in enumerate(self.watchlist):
+            h = self.watch_history.get(watch_key(entry)) or {}
+            for j, (metric, title) in enumerate(WATCH_METRICS):
+                ax = self.watch_fig.add_subplot(rows, len(WATCH_METRICS), i * len(WATCH_METRICS) + j + 1)
+                values = [float("nan") if v is None else v * scale.get(metric, 1) for v in h.get(metric) or []]
+                ax.plot(range(len(values)), values, linewidth=1)
+                ax.set_xticks([])
+                ax.tick_params(labelsize=6)
+                ax.set_ylim(bottom=0)
+                if i == 0:
+                    ax.set_title(title, fontsize=8)
+                if j == 0:
+                    ax.set_ylabel(watch_label(entry), fontsize=7, rotation=0, ha="right", va="center")
+        self.watch_fig.tight_layout()
+        self.watch_canvas.draw_idle()
+
+    def _pin(self, entries):
+        known = {watch_key(e) for e in self.watchlist}
+        new = [e for e in entries if watch_key(e) not in known]
+        room = WATCH_MAX - len(self.watchlist)
+        if len(new) > room:
+            messagebox.showwarning(APP_NAME, f"The watchlist holds at most {WATCH_MAX} entries; "
+                                             f"{len(new) - max(room, 0)} not pinned.")
+            new = new[:max(room, 0)]
+        self.watchlist += new
+        self._save_watchlist()
+        self._update_watchlist()
+
+    def pin_selected_processes(self):
+        targets = self._selected_processes()
+        plist = {p['pid']: p for p in (getattr(self, "latest_snapshot", None) or {}).get('processes') or []}
+        self._pin([{"kind": "pid", "pid": pid, "create_time": plist[pid].get('create_time'), "name": name}
+                   for pid, name in targets if pid in plist])
+
+    def pin_name_pattern(self):
+        pattern = simpledialog.askstring(APP_NAME, "Pin every process whose name matches (glob, e.g. postgres*):", parent=self.root)
+        if pattern and pattern.strip():
+            self._pin([{"kind": "name", "pattern": pattern.strip()}])
+
+    def unpin_selected(self):
+        for i in sorted(self.watch_listbox.curselection(), reverse=True):
+            self.watch_history.pop(watch_key(self.watchlist[i]), None)
+            del self.watchlist[i]
+        self._save_watchlist()
+        self._update_watchlist()
+
+    def _save_watchlist(self):
+        try:
+            save_settings({"patterns": [e['pattern'] for e in self.watchlist if e["kind"] == "name"]}, WATCHLIST_PATH)
+        except OSError as e:
+            messagebox.showerror(APP_NAME, f"Could not save the watchlist: {e}")
+
+    # ---------------- process events ----------------
+    @staticmethod
+    def _event_values(e):
+        exited = e['event'] == "exited"
+        return (e['time'], e['event'], e['pid'], e.get('name') or "",
+                format_cpu_time(e.get('lifetime')) if exited else "",
+                human_bytes(e.get('peak_rss')) if exited else "",
+                format_cpu_time(e.get('cpu_time')) if exited else "",
+                e.get('cmdline') or "")
+
+    def _add_events(self, events):
+        if not events:
+            return
+        self.shown_events = (self.shown_events + list(events))[-EVENT_LIST_MAX:]
+        if str(self.event_tree) in self.table_sorts:
+            self._draw_events()  # new events go wherever the heading sort puts them
+            return
+        for e in events:
+            self.event_tree.insert("", 0, tags=(e['event'],), values=self._event_values(e))
+        extra = self.event_tree.get_children()[EVENT_LIST_MAX:]
+        if extra:
+            self.event_tree.delete(*extra)
+        self._update_event_stats()
+
+    def _show_events(self, events):
+        self.shown_events = list(events)[-EVENT_LIST_MAX:]
+        self._draw_events()
+
+    def _draw_events(self):
+        """Redraw the Events tab from shown_events: newest first, or by the heading sort."""
+        self.event_tree.delete(*self.event_tree.get_children())
+        rows = [(str(i), "", "", self._event_values(e)) for i, e in reversed(list(enumerate(self.shown_events)))]
+        sort = self.table_sorts.get(str(self.event_tree))
+        if sort:
+            rows = sort_table_rows(rows, list(self.event_tree["columns"]).index(sort[0]), sort[1])
+        for _, _, _, values in rows:
+            self.event_tree.insert("", tk.END, tags=(values[1],), values=values)
+        self._update_event_stats()
+
+    def _update_event_stats(self):
+        """Point out crash loops and short-lived spawners: names started most often in the list."""
+        counts = {}
+        for e in self.shown_events:
+            if e['event'] == "started":
+                counts[e.get('name')] = counts.get(e.get('name'), 0) + 1
+        top = sorted(((n, c) for n, c in counts.items() if c > 1), key=lambda nc: -nc[1])[:5]
+        self.event_stats_var.set("Started most often: " + ", ".join(f"{n} ×{c}" for n, c in top) if top else "")
+
+    def clear_events(self):
+        self.event_log = []
+        self._show_events([])
+
+    # ---------------- replay ----------------
+    def open_recording(self):
+        fname = filedialog.askopenfilename(filetypes=[("Recordings","*.json *.jsonl *.jsonl.gz"),("All Files","*")])
+        if not fname:
+            return
+        try:
+            snaps = load_recording(fname)
+        except (OSError, ValueError) as e:
+            messagebox.showerror(APP_NAME, f"Cannot open recording: {e}")
+            return
+        if not snaps:
+            messagebox.showwarning(APP_NAME, "The recording contains no snapshots.")
+            return
+        self._stop_replay_timer()
+        if self.replay_snapshots is None:
+            self.live_history = {a: getattr(self, a) for a in HISTORY_ATTRS}
+        self.replay_snapshots = snaps
+        self.replay_path = fname
+        self.replay_index = -1
+        self.replay_scale.config(to=max(len(snaps) - 1, 0))
+        if not self.replay_bar.winfo_ismapped():
+            self.replay_bar.pack(side=tk.BOTTOM, fill=tk.X, before=self.main_frame)
+        self._show_replay_frame(0)
+
+    def close_recording(self):
+        self._stop_replay_timer()
+        self.replay_snapshots = None
+        self.replay_path = None
+        self.replay_bar.pack_forget()
+        self._swap_history(self.live_history)
+        self.live_history = {}
+        self._update_charts()
+        self._show_events(self.event_log)
+        self.status_var.set("Back to live data")
+
+    def toggle_replay_play(self):
+        if self.replay_snapshots is None:
+            return
+        if self.replay_playing:
+            self._stop_replay_timer()
+            return
+        if self.replay_index >= len(self.replay_snapshots) - 1:
+            self._show_replay_frame(0)
+        self.replay_playing = True
+        self.replay_play_btn.config(text="Pause")
+        self._schedule_replay_step()
+
+    def _stop_replay_timer(self):
+        self.replay_playing = False
+        if self.replay_job is not None:
+            self.root.after_cancel(self.replay_job)
+            self.replay_job = None
+        self.replay_play_btn.config(text="Play")
+
+    def _schedule_replay_step(self):
+        # wait as long as the recording did between these two snapshots, scaled by speed
+        cur = snapshot_time(self.replay_snapshots[self.replay_index])
+        nxt = snapshot_time(self.replay_snapshots[min(self.replay_index + 1, len(self.replay_snapshots) - 1)])
+        gap = (nxt - cur).total_seconds() if cur and nxt else 1.0
+        speed = float(self.replay_speed.get().rstrip("x") or 1)
+        delay_ms = int(min(max(gap, 0.0), 60.0) * 1000 / speed)
+        self.replay_job = self.root.after(max(delay_ms, 20), self._replay_step)
+
+    def _replay_step(self):
+        self.replay_job = None
+        if not self.replay_playing or self.replay_snapshots is None:
+            return
+        if self.replay_index >= len(self.replay_snapshots) - 1:
+            self._stop_replay_timer()
+            return
+        self._show_replay_frame(self.replay_index + 1)
+        self._schedule_replay_step()
+
+    def _on_replay_scrub(self, value):
+        if self.replay_snapshots is None:
+            return
+        idx = int(round(float(value)))
+        if idx != self.replay_index:
+            self._show_replay_frame(idx)
+
+    def _show_replay_frame(self, idx):
+        snaps = self.replay_snapshots
+        idx = min(max(idx, 0), len(snaps) - 1)
+        self.replay_index = idx
+        window = snaps[max(0, idx - CHART_POINTS + 1):idx + 1]
+        self.time_history = [snapshot_time(w) for w in window]
+        self.cpu_history = [w['cpu']['total_percent'] for w in window]
+        self.mem_history = [w['memory']['virtual']['percent'] for w in window]
+        self.nic_history, self.blk_history, self.watch_history, self.user_history = {}, {}, {}, {}
+        for w in window:
+            self._push_nic_history(w)
+            self._push_blk_history(w)
+            self._push_watch_history(w)
+            self._push_user_history(w)
+        self._apply_snapshot(snaps[idx], live=False)
+        self._show_events([e for w in snaps[:idx + 1] for e in w.get('events') or []][-EVENT_LIST_MAX:])
+        self.replay_scale.set(idx)
+        self.replay_pos_var.set(f"{snaps[idx]['timestamp']}  ({idx + 1}/{len(snaps)})")
+        self.status_var.set(f"Replaying {os.path.basename(self.replay_path)}")
+
+    def refresh_processes(self):
+        """Re-render the process table from the latest snapshot (live or replayed). Sampling
+        happens only on the sampler thread, so every sample also goes through _apply_snapshot."""
+        self._update_full_memory()
+        snap = getattr(self, "latest_snapshot", None)
+        if snap is None:
+            return
+        plist = snap['processes']
+        sort_processes(plist, self.sort_by.get())
+        matches = self._filter_processes(plist)
+        if self.tree_mode.get():
+            rows = self._process_tree_rows(plist, matches)
+        elif self.group_mode.get():
+            rows = self._process_group_rows(matches)
         else:
-            snap = self.sampler.snapshot()
-            plist = snap['processes']
+            rows = [(process_iid(p), "", "", self._process_values(p)) for p in matches]
+        self._sync_tree(self.proc_tree, rows, expand=not self.group_mode.get())
+        # rows _sync_tree deleted and re-inserted (filter, mode change) come back untagged
+        leaking = {process_iid(p) for p in plist if p.get('leak_rate')}
+        leaking |= {parent for iid, parent, _, _ in rows if iid in leaking and parent.startswith(GROUP_IID_PREFIX)}
+        leaking = {iid for iid in leaking if self.proc_tree.exists(iid)}
+        for iid in leaking:
+            if "leak" not in self.proc_tree.item(iid, "tags"):
+                self.proc_tree.item(iid, tags=("leak",))
+        for iid in self._leak_rows - leaking:
+            if self.proc_tree.exists(iid):
+                self.proc_tree.item(iid, tags=())
+        self._leak_rows = leaking
+
+    def _update_full_memory(self):
+        """Sample USS/PSS/swap while the table uses them, and always while recording so sessions have them."""
+        self.sampler.full_memory = self.logging_enabled or needs_full_memory(
+            self.sort_by.get(), self.proc_search.get(), self.proc_columns)
+
+    def _filter_processes(self, plist):
+        """Apply the filter box. While it doesn't parse, say why and keep the last good filter."""
+        text = self.proc_search.get()
+        if text != self._filter_text:
+            try:
+                self._filter_pred = parse_process_filter(text) if text.strip() else None
+            except ProcessFilterError as e:
+                self.filter_error.config(text=f"Filter error: {e}")
+                self.filter_error.pack(fill=tk.X, padx=4, before=self.proc_tree)
+            else:
+                self.filter_error.pack_forget()
+            self._filter_text = text
+        return [p for p in plist if self._filter_pred(p)] if self._filter_pred else plist
+
+    def _apply_saved_filter(self):
+        self.proc_search.delete(0, tk.END)
+        self.proc_search.insert(0, self.saved_filters.get(self.filter_choice.get(), ""))
+        self.refresh_processes()
+
+    def save_current_filter(self):
+        text = self.proc_search.get().strip()
+        if not text:
+            messagebox.showwarning(APP_NAME, "Type a filter expression first.")
+            return
+        try:
+            parse_process_filter(text)
+        except ProcessFilterError as e:
+            messagebox.showerror(APP_NAME, f"Filter error: {e}")
+            return
+        name = simpledialog.askstring(APP_NAME, "Save filter as:", initialvalue=self.filter_choice.get() or text, parent=self.root)
+        if not name:
+            return
+        self.saved_filters[name] = text
+        self._store_saved_filters()
+        self.filter_choice.set(name)
+
+    def delete_saved_filter(self):
+        name = self.filter_choice.get()
+        if name in self.saved_filters and messagebox.askyesno(APP_NAME, f"Delete saved filter {name!r}?"):
+            del self.saved_filters[name]
+            self._store_saved_filters()
+            self.filter_choice.set("")
+
+    def _store_saved_filters(self):
+        try:
+            save_settings(self.saved_filters, SAVED_FILTERS_PATH)
+        except OSError as e:
+            messagebox.showerror(APP_NAME, f"Could not save filters: {e}")
+        self.filter_choice.config(values=sorted(self.saved_filters))
+
+    def _process_values(self, p, total=None):
+        values = (
+            p['pid'], p['name'], p.get('username'), pct_str(p.get('cpu_percent')),
+            format_cpu_time(p.get('cpu_time')), format_cpu_time(p.get('cpu_user')), format_cpu_time(p.get('cpu_system')),
+            f"{p.get('memory_percent') or 0:.1f}", human_bytes(p.get('memory_rss')), p.get('num_threads'),
+            io_rate_str(p.get('io_read_per_sec')), io_rate_str(p.get('io_write_per_sec')), p.get('status'), leak_str(p.get('leak_rate')),
+            p.get('ppid'), nice_str(p.get('nice')), p.get('ionice') or "--", affinity_str(p.get('cpu_affinity'), self.cpu_count),
+            format_start_time(p.get('create_time')), p.get('cmdline'), p.get('num_fds'), p.get('ctx_switches'),
+            human_bytes(p['memory_uss']) if p.get('memory_uss') is not None else "--",
+            human_bytes(p['memory_pss']) if p.get('memory_pss') is not None else "--",
+            human_bytes(p['memory_swap']) if p.get('memory_swap') is not None else "--",
+            p.get('terminal'), p.get('cwd')
+        )
+        if total:
+            values += (f"{total['cpu']:.1f}", human_bytes(total['rss']), total['threads'], total['count'])
+        return values
+
+    def _process_tree_rows(self, plist, matches):
+        """Pre-order (iid, parent, text, values) rows nesting plist by ppid. Only matches and
+        their ancestors are shown; siblings follow the sort order, using subtree totals for cpu/memory."""
+        by_pid = {p['pid']: p for p in plist}
+        roots, children, totals = build_process_tree(plist)
+        shown = set()
+        for p in matches:
+            pid = p['pid']
+            while pid in by_pid and pid not in shown:
+                shown.add(pid)
+                pid = by_pid[pid].get('ppid')
         key = self.sort_by.get()
-        if key == "memory":
-            plist.sort(key=lambda x: (x['memory_percent'] or 0), reverse=True)
+        rank = {pid: i for i, pid in enumerate(p['pid'] for p in plist)}  # plist is already sorted
+        if key in ("cpu", "memory"):
+            field = "cpu" if key == "cpu" else "rss"
+            order = lambda pid: -totals[pid][field]
+        else:
+            order = lambda pid: rank[pid]
+        rows = []
+        stack = [(pid, "") for pid in sorted((r for r in roots if r in shown), key=order, reverse=True)]
+        while stack:
+            pid, parent = stack.pop()
+            p = by_pid[pid]
+            rows.append((process_iid(p), parent, f"{p['name']} ({pid})", self._process_values(p, totals[pid])))
+            kids = sorted((c for c in children.get(pid, []) if c in shown), key=order, reverse=True)
+            stack.extend((c, process_iid(p)) for c in kids)
+        return rows
+
+    def _process_group_rows(self, plist):
+        """One collapsed row per app (see process_group_key) with summed values, its members
+        nested below. Apps with a single process stay plain rows. Groups follow the sort order by
+        their sums; the Procs column holds the member count."""
+        groups = {}
+        for p in plist:  # plist is already sorted, so members are too
+            groups.setdefault(process_group_key(p), []).append(p)
+        weight = PROCESS_SORTS.get(self.sort_by.get(), PROCESS_SORTS["cpu"])
+        rows = []
+        for key, members in sorted(groups.items(), key=lambda kv: -sum(weight(p) for p in kv[1])):
+            name = members[0].get('name') or "?"
+            g = group_totals(name, members)
+            total = {"cpu": g['cpu_percent'] or 0.0, "rss": g['memory_rss'] or 0, "threads": g['num_threads'] or 0,
+                     "count": len(members)}
+            if len(members) == 1:
+                rows.append((process_iid(members[0]), "", "", self._process_values(members[0], total)))
+                continue
+            iid = GROUP_IID_PREFIX + key
+            rows.append((iid, "", f"{name} ({len(members)})", self._process_values(g, total)))
+            rows.extend((process_iid(p), iid, f"{p.get('name')} ({p['pid']})", self._process_values(p)) for p in members)
+        return rows
+
+    def _set_view_mode(self, var):
+        """Tree view and group-by-app are exclusive; var is the one just toggled."""
+        for other in (self.tree_mode, self.group_mode):
+            if other is not var:
+                other.set(False)
+        nested = self.tree_mode.get() or self.group_mode.get()
+        self.proc_tree.config(show="tree headings" if nested else "headings", displaycolumns=self._proc_display_columns())
+        # rows change shape (nested vs flat), so start from an empty table
+        self.proc_tree.delete(*self.proc_tree.get_children())
+        self._tree_values.pop(str(self.proc_tree), None)
+        self._leak_rows = set()
+        self.refresh_processes()
+
+    def _toggle_freeze(self):
+        if self.freeze_procs.get():
+            self.status_var.set("Process table frozen; Refresh still updates it once")
+        else:
+            self.refresh_processes()
+
+    def _proc_display_columns(self):
+        if self.tree_mode.get():
+            return tuple(self.proc_columns) + TREE_COLUMNS
+        return tuple(self.proc_columns) + (("tcount",) if self.group_mode.get() else ())
+
+    def _apply_proc_columns(self):
+        self.proc_tree.config(displaycolumns=self._proc_display_columns())
+        self.sampler.extra_attrs = frozenset(COLUMN_ATTRS[c] for c in self.proc_columns if c in COLUMN_ATTRS)
+
+    def choose_columns(self):
+        headings = dict(PROC_HEADINGS)
+        columns = ask_columns(self.root, [(c, headings[c]) for c in PROC_COLUMNS + EXTRA_COLUMNS], self.proc_columns)
+        if columns is None:
+            return
+        self.proc_columns = columns
+        self._apply_proc_columns()
+        self._save_column_settings()
+        self.refresh_processes()  # newly sampled columns fill in with the next snapshot
+
+    def _save_column_settings(self):
+        widths = {c: self.proc_tree.column(c, "width") for c in self.proc_tree["columns"]}
+        try:
+            hidden = [c for c in PROC_COLUMNS if c not in self.proc_columns]
+            save_settings({"columns": self.proc_columns, "hidden": hidden, "widths": widths}, COLUMNS_PATH)
+        except OSError:
+            pass  # not worth interrupting the user over; defaults come back next run
+
+    def on_close(self):
+        self._save_column_settings()
+        self.root.destroy()
+
+    # ---------------- heading sort ----------------
+    def _make_sortable(self, tree, headings):
+        """Set heading texts and make clicking a heading sort the table by that column."""
+        self._headings[str(tree)] = dict(headings)
+        for col, txt in headings:
+            tree.heading(col, text=txt, command=lambda c=col: self._sort_by_heading(tree, c))
+
+    def _sort_by_heading(self, tree, col):
+        prev = self.table_sorts.get(str(tree))
+        # a new column starts with the biggest values first, except text-like ones
+        descending = not prev[1] if prev and prev[0] == col else col not in TEXT_SORT_COLUMNS
+        self.table_sorts[str(tree)] = (col, descending)
+        self._show_sort_arrow(tree)
+        self._refresh_table(tree)
+
+    def _clear_heading_sort(self, tree):
+        self.table_sorts.pop(str(tree), None)
+        self._show_sort_arrow(tree)
+        self._refresh_table(tree)
+
+    def _show_sort_arrow(self, tree):
+        col, descending = self.table_sorts.get(str(tree), (None, False))
+        for c, txt in self._headings[str(tree)].items():
+            tree.heading(c, text=txt + (" ▼" if descending else " ▲") if c == col else txt)
+
+    def _refresh_table(self, tree):
+        """Redraw one table from the latest snapshot, e.g. after its sort changed."""
+        s = getattr(self, "latest_snapshot", None)
+        if tree is self.proc_tree:
+            self.refresh_processes()
+        elif tree is self.event_tree:
+            self._draw_events()
+        elif s and tree is self.disk_tree:
+            self._refresh_disk_tree(s['disk']['partitions'])
+        elif s and tree is self.blk_tree:
+            self._refresh_blk_tree(s['disk'].get('devices') or [])
+        elif s and tree is self.nic_tree:
+            self._refresh_nic_tree(s['network'].get('interfaces') or [])
+        elif s and tree in (self.user_tree, self.session_tree):
+            self._refresh_user_tables(s)
+
+    def _sync_tree(self, tree, rows, expand=True):
+        """Make a Treeview show rows [(iid, parent_iid, text, values), ...], touching only what changed.
+
+        rows are in display order with parents before their children (parent "" for top level).
+        New top-level rows start expanded unless expand is False.
+        Rows are keyed by iid, so a refresh with thousands of processes only updates the values
+        that differ, keeps selection, focus and expand/collapse state, and reorders each level
+        in a single call. In flat tables the row at the top of the view stays there. A sort
+        picked by clicking a heading overrides the given order (see sort_table_rows)."""
+        cache = self._tree_values.setdefault(str(tree), {})  # iid -> (parent, text, values)
+        sort = self.table_sorts.get(str(tree))
+        if sort:
+            rows = sort_table_rows(rows, list(tree["columns"]).index(sort[0]), sort[1])
+        shown = tree.get_children()
+        top = shown[min(len(shown) - 1, int(round(tree.yview()[0] * len(shown))))] if shown else None
+        order = {}
+        for iid, parent, text, values in rows:
+            values = tuple("" if v is None else v for v in values)
+            old = cache.get(iid)
+            if old is None:
+                tree.insert(parent, tk.END, iid=iid, text=text, values=values, open=expand and not parent)
+            else:
+                if old[0] != parent:
+                    tree.move(iid, parent, tk.END)
+                if old[1:] != (text, values):
+                    tree.item(iid, text=text, values=values)
+            cache[iid] = (parent, text, values)
+            order.setdefault(parent, []).append(iid)
+        wanted = {iid for iid, _, _, _ in rows}
+        for iid in [iid for iid in cache if iid not in wanted]:
+            if tree.exists(iid):  # may already be gone with a deleted parent
+                tree.delete(iid)
+            del cache[iid]
+        for parent, kids in order.items():
+            if list(tree.get_children(parent)) != kids:
+                tree.set_children(parent, *kids)  # one Tcl call reorders the level
+        if top in cache and top != shown[0] and order.keys() == {""}:  # scrolled flat table
+            kids = order[""]
+            tree.yview_moveto(kids.index(top) / len(kids))
+
+    def _on_proc_double_click(self, event):
+        iid = self.proc_tree.identify_row(event.y)
+        if iid and not iid.startswith(GROUP_IID_PREFIX):  # double-click on a group just expands it
+            self.show_process_details(int(self.proc_tree.item(iid, 'values')[0]))
+
+    def export_memory_history(self):
+        target = self._selected_process()
+        if not target:
+            return
+        pid, name = target
+        proc = next((p for p in (getattr(self, "latest_snapshot", None) or {}).get('processes', []) if p['pid'] == pid), None)
+        points = self.sampler.memory_history(pid, proc['create_time']) if proc and self.replay_snapshots is None else []
+        if not points:
+            messagebox.showwarning(APP_NAME, f"No memory history recorded for {name} (PID {pid}) yet.\n"
+                                             f"RSS is sampled every {LEAK_SAMPLE_SECONDS} seconds while the app runs live.")
+            return
+        fname = filedialog.asksaveasfilename(defaultextension=".json", initialfile=f"memory_{name}_{pid}.json",
+                                             filetypes=[("JSON","*.json"),("CSV","*.csv")])
+        if not fname:
+            return
+        slope, r2 = memory_trend(points)
+        try:
+            with open(fname, "w", newline="") as f:
+                if fname.lower().endswith(".csv"):
+                    f.write("time,rss_bytes\n")
+                    f.writelines(f"{datetime.datetime.fromtimestamp(t).isoformat(sep=' ', timespec='seconds')},{rss}\n" for t, rss in points)
+                else:
+                    json.dump({"pid": pid, "name": name, "create_time": proc['create_time'],
+                               "growth_bytes_per_hour": slope * 3600, "r2": r2, "suspected_leak": proc.get('leak_rate') is not None,
+                               "samples": [{"time": datetime.datetime.fromtimestamp(t).isoformat(sep=' ', timespec='seconds'), "rss": rss}
+                                           for t, rss in points]}, f, indent=2)
+        except OSError as e:
+            messagebox.showerror(APP_NAME, f"Could not export memory history: {e}")
+            return
+        self.status_var.set(f"Memory history exported: {fname}")
+
+    def show_process_details(self, pid):
+        try:
+            ProcessDetailWindow(self.root, pid)
+        except psutil.NoSuchProcess:
+            messagebox.showwarning(APP_NAME, f"Process {pid} no longer exists.")
+        except psutil.AccessDenied:
+            messagebox.showerror(APP_NAME, f"Access denied to process {pid}.")
+
+    # ---------------- signals ----------------
+    def _on_proc_context_menu(self, event):
+        iid = self.proc_tree.identify_row(event.y)
+        if iid:
+            if iid not in self.proc_tree.selection():  # keep a multi-selection the click lands in
+                self.proc_tree.selection_set(iid)
+            self.proc_tree.focus(iid)
+            self.proc_menu.tk_popup(event.x_root, event.y_root)
+
+    def _selected_processes(self):
+        """[(pid, name), ...] for every selected row, a selected group standing for all its
+        members; empty after telling the user to select one."""
+        iids = []
+        for iid in self.proc_tree.selection():
+            iids.extend(self.proc_tree.get_children(iid) if iid.startswith(GROUP_IID_PREFIX) else [iid])
+        targets = list(dict.fromkeys((int(v[0]), v[1]) for v in (self.proc_tree.item(iid, 'values') for iid in iids)))
+        if not targets:
+            messagebox.showwarning(APP_NAME, "Select a process first.")
+        return targets
+
+    def _selected_process(self):
+        """(pid, name) of the first selected row, or None after telling the user to select one."""
+        targets = self._selected_processes()
+        return targets[0] if targets else None
+
+    def _with_selected(self, action):
+        target = self._selected_process()
+        if target:
+            action(target[0])
+
+    def _confirm_targets(self, question, targets):
+        """One yes/no dialog for an action on every target: "Terminate these 3 processes?" + a list."""
+        if len(targets) == 1:
+            pid, name = targets[0]
+            return messagebox.askyesno(APP_NAME, f"{question} {name} (PID {pid})?")
+        return messagebox.askyesno(APP_NAME, f"{question} these {len(targets)} processes?\n\n" + describe_targets(targets))
+
+    def _run_batch(self, what, targets, action, sched=None):
+        """Apply action(pid) to every target and report which succeeded and which failed.
+        sched names the scheduling change ("nice", ...) so permission failures explain themselves."""
+        done, failed = [], []
+        for pid, name in targets:
+            try:
+                action(pid)
+            except (psutil.Error, OSError, ValueError) as e:
+                failed.append((pid, name, e))
+            else:
+                done.append((pid, name))
+        if len(targets) == 1 and failed:
+            pid, name, e = failed[0]
+            messagebox.showerror(APP_NAME, scheduling_error_message(sched, pid, name, e) if sched
+                                 else f"Could not {what} {name} (PID {pid}): {e}")
+        elif len(targets) == 1:
+            pid, name = done[0]
+            self.status_var.set(f"{what[0].upper()}{what[1:]} {name} (PID {pid}): done")
+        else:
+            summary = batch_summary(what, done, failed, sched)
+            self.status_var.set(summary.splitlines()[0])
+            (messagebox.showwarning if failed else messagebox.showinfo)(APP_NAME, summary)
+        self.refresh_processes()
+
+    def signal_selected_process(self, sig):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        label = sig if isinstance(sig, str) else f"signal {sig}"
+        if sig != "SIGCONT" and not self._confirm_targets(f"Send {label} to", targets):
+            return
+        self._run_batch(f"send {label} to", targets, lambda pid: send_process_signal(pid, sig))
+
+    def signal_selected_process_prompt(self):
+        if not self._selected_processes():
+            return
+        text = simpledialog.askstring(APP_NAME, "Signal name or number (e.g. SIGUSR1, HUP, 10):", parent=self.root)
+        if not text:
+            return
+        try:
+            sig = parse_signal(text)
+        except ValueError as e:
+            messagebox.showerror(APP_NAME, str(e))
+            return
+        self.signal_selected_process(sig)
+
+    def escalate_selected_process(self):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        label = f"{targets[0][1]} (PID {targets[0][0]})" if len(targets) == 1 else f"{len(targets)} processes"
+        wait = simpledialog.askinteger(APP_NAME, f"Send SIGTERM to {label}, then SIGKILL after how many seconds?",
+                                       initialvalue=ESCALATE_SECONDS, minvalue=0, parent=self.root)
+        if wait is None:
+            return
+        procs, errors = [], []
+        for pid, name in targets:
+            try:
+                procs.append(psutil.Process(pid))
+            except psutil.Error as e:
+                errors.append(f"PID {pid}: {e}")
+        if not procs:
+            messagebox.showerror(APP_NAME, "Error:\n" + "\n".join(errors))
+            return
+        self._start_escalation(procs, label, wait, errors)
+
+    def kill_selected_tree(self):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        procs, errors = {}, []
+        for pid, name in targets:
+            try:
+                tree = process_tree_kill_order(pid)
+            except psutil.Error as e:
+                errors.append(f"PID {pid}: {e}")
+                continue
+            for p in tree:  # a selected process may already be in another selected one's tree
+                procs.setdefault(p.pid, p)
+        if not procs:
+            messagebox.showerror(APP_NAME, "Error:\n" + "\n".join(errors))
+            return
+        if len(targets) == 1:
+            pid, name = targets[0]
+            label = f"{name} (PID {pid}) and its children"
+            question = f"Terminate {name} (PID {pid}) and its {len(procs) - 1} descendant process(es)?"
+        else:
+            label = f"{len(targets)} processes and their children"
+            question = (f"Terminate these {len(targets)} processes and their descendants ({len(procs)} processes in all)?\n\n"
+                        + describe_targets(targets))
+        if not messagebox.askyesno(APP_NAME, f"{question}\nSurvivors are killed after {ESCALATE_SECONDS} seconds."):
+            return
+        self._start_escalation(list(procs.values()), label, ESCALATE_SECONDS, errors)
+
+    def _start_escalation(self, procs, label, wait, errors=None):
+        """SIGTERM every process (in the given order), then poll without blocking the UI and
+        SIGKILL whatever is still running after wait seconds."""
+        errors = list(errors or [])
+        for p in procs:
+            try:
+                p.terminate()
+            except psutil.NoSuchProcess:
+                pass
+            except psutil.Error as e:
+                errors.append(f"PID {p.pid}: {e}")
+        self.status_var.set(f"Sent SIGTERM to {label}; waiting up to {wait}s")
+        self._poll_escalation(procs, label, time.monotonic() + wait, False, errors)
+
+    def _poll_escalation(self, procs, label, deadline, killed, errors):
+        alive = [p for p in procs if not process_exited(p)]
+        if alive and time.monotonic() < deadline:
+            self.root.after(200, lambda: self._poll_escalation(procs, label, deadline, killed, errors))
+            return
+        if alive and not killed:
+            for p in alive:
+                try:
+                    p.kill()
+                except psutil.NoSuchProcess:
+                    pass
+                except psutil.Error as e:
+                    errors.append(f"PID {p.pid}: {e}")
+            self.status_var.set(f"Sent SIGKILL to {len(alive)} process(es) of {label}")
+            self._poll_escalation(procs, label, time.monotonic() + 2, True, errors)
+            return
+        if not alive:
+            result = f"{label} exited after {'SIGKILL' if killed else 'SIGTERM'}."
+        else:
+            result = f"{label}: still running after SIGKILL: " + ", ".join(str(p.pid) for p in alive)
+        if errors:
+            result += "\n\nErrors:\n" + "\n".join(errors)
+        self.status_var.set(result.splitlines()[0])
+        (messagebox.showinfo if not alive and not errors else messagebox.showwarning)(APP_NAME, result)
+        self.refresh_processes()
+
+    # ---------------- scheduling ----------------
+    def renice_selected_process(self):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        pid, name = targets[0]
+        label = f"{name} (PID {pid})" if len(targets) == 1 else f"{len(targets)} processes"
+        try:
+            current = psutil.Process(pid).nice()
+        except psutil.Error as e:
+            messagebox.showerror(APP_NAME, scheduling_error_message("nice", pid, name, e))
+            return
+        if psutil.WINDOWS:
+            classes = available_priority_classes()
+            choice = ask_choice(self.root, "Set Priority", f"Priority class for {label}:",
+                                [l for l, _ in classes], initial=nice_str(current))
+            if choice is None:
+                return
+            value = dict(classes)[choice]
+        else:
+            value = simpledialog.askinteger(APP_NAME, f"Nice value for {label}, -20 (highest) to 19 (lowest):",
+                                            initialvalue=current, minvalue=-20, maxvalue=19, parent=self.root)
+            if value is None:
+                return
+        self._run_batch(f"set priority {nice_str(value)} for", targets, lambda p: set_process_nice(p, value), sched="nice")
+
+    def ionice_selected_process(self):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        pid, name = targets[0]
+        label = f"{name} (PID {pid})" if len(targets) == 1 else f"{len(targets)} processes"
+        try:
+            current = ionice_label(psutil.Process(pid).ionice())
+        except psutil.Error as e:
+            messagebox.showerror(APP_NAME, scheduling_error_message("ionice", pid, name, e))
+            return
+        classes = available_ionice_classes()
+        choice = ask_choice(self.root, "Set I/O Priority", f"I/O scheduling class for {label}:",
+                            [l for l, _, _ in classes], initial=current.split("/")[0])
+        if choice is None:
+            return
+        _, ioclass, takes_level = next(c for c in classes if c[0] == choice)
+        level = None
+        if takes_level:
+            level = simpledialog.askinteger(APP_NAME, f"{choice} level for {label}, 0 (highest) to 7 (lowest):",
+                                            initialvalue=4, minvalue=0, maxvalue=7, parent=self.root)
+            if level is None:
+                return
+        what = f"set I/O priority {choice}" + (f"/{level}" if level is not None else "") + " for"
+        self._run_batch(what, targets, lambda p: set_process_ionice(p, ioclass, level), sched="ionice")
+
+    def affinity_selected_process(self):
+        targets = self._selected_processes()
+        if not targets:
+            return
+        pid, name = targets[0]
+        label = f"{name} (PID {pid})" if len(targets) == 1 else f"{len(targets)} processes"
+        try:
+            current = psutil.Process(pid).cpu_affinity()
+        except psutil.Error as e:
+            messagebox.showerror(APP_NAME, scheduling_error_message("affinity", pid, name, e))
+            return
+        cpus = ask_cpu_affinity(self.root, f"CPUs {label} may run on:", self.cpu_count, current)
+        if cpus is None:
+            return
+        self._run_batch(f"set CPU affinity {affinity_str(cpus, self.cpu_count)} for", targets,
+                        lambda p: set_process_affinity(p, cpus), sched="affinity")
+
+    def kill_selected_process(self):
+        targets = self._selected_processes()
+        if targets and self._confirm_targets("Terminate", targets):
+            self._run_batch("terminate", targets, terminate_process)
+
+def ask_choice(root, title, prompt, choices, initial=None):
+    """Modal dialog picking one of choices from a dropdown. Returns the choice, or None if cancelled."""
+    win = Toplevel(root)
+    win.title(title)
+    win.transient(root)
+    win.resizable(False, False)
+    result = []
+    ttk.Label(win, text=prompt).pack(padx=10, pady=(10,4), anchor=tk.W)
+    box = ttk.Combobox(win, values=choices, state="readonly")
+    box.set(initial if initial in choices else choices[0])
+    box.pack(fill=tk.X, padx=10)
+    def ok():
+        result.append(box.get())
+        win.destroy()
+    btns = ttk.Frame(win)
+    btns.pack(pady=10)
+    ttk.Button(btns, text="OK", command=ok).pack(side=tk.LEFT, padx=4)
+    ttk.Button(btns, text="Cancel", command=win.destroy).pack(side=tk.LEFT, padx=4)
+    win.bind("<Return>", lambda e: ok())
+    win.bind("<Escape>", lambda e: win.destroy())
+    win.grab_set()
+    root.wait_window(win)
+    return result[0] if result else None
+
+def ask_cpu_affinity(root, prompt, cpu_count, current):
+    """Modal per-core checkbox picker. Returns the chosen CPU numbers, or None if cancelled."""
+    win = Toplevel(root)
+    win.title("Set CPU Affinity")
+    win.transient(root)
+    win.resizable(False, False)
+    result = []
+    ttk.Label(win, text=prompt).pack(padx=10, pady=(10,4), anchor=tk.W)
+    grid = ttk.Frame(win)
+    grid.pack(padx=10)
+    cols = 8 if cpu_count > 8 else cpu_count
+    checks = [tk.BooleanVar(value=c in current) for c in range(cpu_count)]
+    for c, var in enumerate(checks):
+        ttk.Checkbutton(grid, text=f"CPU {c}", variable=var).grid(row=c // cols, column=c % cols, sticky=tk.W, padx=2)
+    error_var = tk.StringVar()
+    ttk.Label(win, textvariable=error_var, foreground="red").pack()
+    def set_all(value):
+        for var in checks:
+            var.set(value)
+    def ok():
+        cpus = [c for c, var in enumerate(checks) if var.get()]
+        if not cpus:
+            error_var.set("Select at least one CPU.")
+            return
+        result.append(cpus)
+        win.destroy()
+    btns = ttk.Frame(win)
+    btns.pack(pady=10)
+    ttk.Button(btns, text="All", command=lambda: set_all(True)).pack(side=tk.LEFT, padx=4)
+    ttk.Button(btns, text="None", command=lambda: set_all(False)).pack(side=tk.LEFT, padx=4)
+    ttk.Button(btns, text="OK", command=ok).pack(side=tk.LEFT, padx=(16,4))
+    ttk.Button(btns, text="Cancel", command=win.destroy).pack(side=tk.LEFT, padx=4)
+    win.bind("<Escape>", lambda e: win.destroy())
+    win.grab_set()
+    root.wait_window(win)
+    return result[0] if result else None
+
+def ask_columns(root, available, shown):
+    """Modal column chooser: available is [(column, heading)], shown the columns currently
+    displayed in order. Returns the new ordered list, or None if cancelled."""
+    win = Toplevel(root)
+    win.title("Process Columns")
+    win.transient(root)
+    headings = dict(available)
+    result = []
+    hidden_list = tk.Listbox(win, height=16, exportselection=False)
+    shown_list = tk.Listbox(win, height=16, exportselection=False)
+    ttk.Label(win, text="Available").grid(row=0, column=0, padx=8, pady=(8,2), sticky=tk.W)
+    ttk.Label(win, text="Shown, in order").grid(row=0, column=2, padx=8, pady=(8,2), sticky=tk.W)
+    hidden_list.grid(row=1, column=0, padx=8, sticky=tk.NSEW)
+    shown_list.grid(row=1, column=2, padx=8, sticky=tk.NSEW)
+    shown = list(shown)
+    def fill():
+        hidden_list.delete(0, tk.END)
+        shown_list.delete(0, tk.END)
+        for col, txt in available:
+            if col not in shown:
+                hidden_list.insert(tk.END, txt)
+        for col in shown:
+            shown_list.insert(tk.END, headings[col])
+    def add():
+        hidden = [c for c, _ in available if c not in shown]
+        for i in hidden_list.curselection():
+            shown.append(hidden[i])
+        fill()
+    def remove():
+        for i in sorted(shown_list.curselection(), reverse=True):
+            del shown[i]
+        fill()
+    def move(delta):
+        sel = shown_list.curselection()
+        if not sel or not 0 <= sel[0] + delta < len(shown):
+            return
+        i = sel[0]
+        shown[i], shown[i + delta] = shown[i + delta], shown[i]
+        fill()
+        shown_list.selection_set(i + delta)
+    def ok():
+        if shown:
+            result.append(list(shown))
+            win.destroy()
+    mid = ttk.Frame(win)
+    mid.grid(row=1, column=1)
+    ttk.Button(mid, text="Add >", command=add).pack(pady=2)
+    ttk.Button(mid, text="< Remove", command=remove).pack(pady=2)
+    ttk.Button(mid, text="Up", command=lambda: move(-1)).pack(pady=(12,2))
+    ttk.Button(mid, text="Down", command=lambda: move(1)).pack(pady=2)
+    btns = ttk.Frame(win)
+    btns.grid(row=2, column=0, columnspan=3, pady=10)
+    ttk.Button(btns, text="OK", command=ok).pack(side=tk.LEFT, padx=4)
+    ttk.Button(btns, text="Cancel", command=win.destroy).pack(side=tk.LEFT, padx=4)
+    hidden_list.bind("<Double-1>", lambda e: add())
+    shown_list.bind("<Double-1>", lambda e: remove())
+    win.bind("<Escape>", lambda e: win.destroy())
+    fill()
+    win.grab_set()
+    root.wait_window(win)
+    return result[0] if result else None
+
+class ProcessDetailWindow:
+    """Toplevel with everything we can learn about one process, refreshed while it is open."""
+    FULL_REFRESH_TICKS = 5  # environment, files, maps... are re-read every N updates
+
+    def __init__(self, root, pid):
+        self.root = root
+        self.pid = pid
+        self.proc = psutil.Process(pid)
+        self.proc.cpu_percent(None)  # baseline for the first live reading
+        self.tick = 0
+        self.cpu_history = []
+        self.rss_history = []
+
+        self.win = Toplevel(root)
+        self.win.title(f"{APP_NAME} - PID {pid}")
+        self.win.geometry("760x560")
+        self.header = ttk.Label(self.win, text=f"PID {pid}", font=("Helvetica", 13, "bold"))
+        self.header.pack(anchor=tk.W, padx=8, pady=(8,2))
+        self.state_var = tk.StringVar(value="")
+        ttk.Label(self.win, textvariable=self.state_var).pack(anchor=tk.W, padx=8)
+
+        nb = ttk.Notebook(self.win)
+        nb.pack(fill=tk.BOTH, expand=True, padx=6, pady=6)
+        general = ttk.Frame(nb)
+        nb.add(general, text="General")
+        self.general_text = tk.Text(general, height=10, wrap=tk.WORD)
+        self.general_text.pack(fill=tk.X, padx=4, pady=4)
+        self.fig = Figure(figsize=(5,2.4), dpi=80)
+        self.cpu_ax = self.fig.add_subplot(121)
+        self.rss_ax = self.fig.add_subplot(122)
+        self.canvas = FigureCanvasTkAgg(self.fig, master=general)
+        self.canvas.get_tk_widget().pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+
+        self.env_tree = self._add_table(nb, "Environment", [("key","Variable",180),("value","Value",480)])
+        self.files_tree = self._add_table(nb, "Open Files", [("fd","FD",60),("path","Path",600)])
+        self.conn_tree = self._add_table(nb, "Connections", [("fd","FD",50),("type","Type",60),("laddr","Local",200),("raddr","Remote",200),("status","Status",100)])
+        self.threads_tree = self._add_table(nb, "Threads", [("id","TID",100),("user","User time",120),("sys","System time",120)])
+        self.limits_tree = self._add_table(nb, "Limits", [("name","Resource",160),("soft","Soft",160),("hard","Hard",160)])
+        maps = ttk.Frame(nb)
+        nb.add(maps, text="Memory Maps")
+        self.maps_text = tk.Text(maps, wrap=tk.NONE)
+        self.maps_text.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+
+        ttk.Button(self.win, text="Close", command=self.win.destroy).pack(pady=6)
+        self.refresh()
+
+    def _add_table(self, nb, title, cols):
+        frame = ttk.Frame(nb)
+        nb.add(frame, text=title)
+        tree = ttk.Treeview(frame, columns=[c for c, _, _ in cols], show='headings')
+        for col, txt, width in cols:
+            tree.heading(col, text=txt)
+            tree.column(col, anchor=tk.W, width=width)
+        tree.pack(fill=tk.BOTH, expand=True, padx=4, pady=4)
+        return tree
+
+    @staticmethod
+    def _fill(tree, rows):
+        tree.delete(*tree.get_children())
+        if rows == ACCESS_DENIED:
+            tree.insert("", tk.END, values=(ACCESS_DENIED,))
+            return
+        for row in rows:
+            tree.insert("", tk.END, values=row)
+
+    def refresh(self):
+        if not self.win.winfo_exists():
+            return
+        full = self.tick % self.FULL_REFRESH_TICKS == 0
+        try:
+            d = collect_process_details(self.proc, full=full)
+            cpu = _proc_call(lambda: self.proc.cpu_percent(None), None)
+        except psutil.NoSuchProcess:
+            self.state_var.set("Process has exited; showing the last known state.")
+            return
+        self.tick += 1
+        self.cpu_history = (self.cpu_history + [cpu or 0.0])[-CHART_POINTS:]
+        rss = d['memory_rss'] if d['memory_rss'] != ACCESS_DENIED else 0
+        self.rss_history = (self.rss_history + [rss / (1024.0 * 1024.0)])[-CHART_POINTS:]
+        self.header.config(text=f"{d['name']} (PID {self.pid})")
+        self.state_var.set(f"Status: {d['status']}   CPU: {pct_str(cpu)}%   RSS: {human_bytes(d['memory_rss']) if rss else d['memory_rss']}"
+                           f"   Threads: {d['num_threads']}")
+        self._show_general(d)
+        self._update_chart()
+        if full:
+            self._show_full(d)
+        self.root.after(UPDATE_INTERVAL_MS, self.refresh)
+
+    def _show_general(self, d):
+        cmd = d['cmdline']
+        started = d['create_time']
+        parents = d['parents']
+        lines = [
+            f"Command line: {' '.join(cmd) if isinstance(cmd, list) else cmd}",
+            f"Executable:   {d['exe']}",
+            f"Working dir:  {d['cwd']}",
+            f"User:         {d['username']}",
+            f"Started:      {datetime.datetime.fromtimestamp(started).isoformat(sep=' ', timespec='seconds') if isinstance(started, float) else started}",
+            f"Parents:      {' <- '.join(f'{name} ({pid})' for pid, name in parents) if isinstance(parents, list) else parents}",
+            f"Memory:       RSS {human_bytes(d['memory_rss']) if d['memory_rss'] != ACCESS_DENIED else ACCESS_DENIED}, "
+            f"VMS {human_bytes(d['memory_vms']) if d['memory_vms'] != ACCESS_DENIED else ACCESS_DENIED}",
+            "",
+            "Memory breakdown:",
+        ]
+        full = d.get('memory_full')
+        if isinstance(full, dict):
+            lines += [f"  {MEMORY_FIELD_LABELS.get(k, k):<28}{human_bytes(v)}" for k, v in full.items()]
+        else:
+            lines.append(f"  {full} (USS/PSS need read access to the process's smaps)")
+        self.general_text.delete(1.0, tk.END)
+        self.general_text.insert(tk.END, "\n".join(lines))
+
+    def _show_full(self, d):
+        env = d['environ']
+        self._fill(self.env_tree, sorted(env.items()) if isinstance(env, dict) else env)
+        self._fill(self.files_tree, d['open_files'])
+        conns = d['connections']
+        self._fill(self.conn_tree, conns if conns == ACCESS_DENIED else
+                   [(c['fd'], c['type'], c['laddr'], c['raddr'], c['status']) for c in conns])
+        threads = d['threads']
+        self._fill(self.threads_tree, threads if threads == ACCESS_DENIED else
+                   [(tid, format_cpu_time(u), format_cpu_time(s)) for tid, u, s in threads])
+        limits = d['rlimits']
+        unlimited = getattr(psutil, "RLIM_INFINITY", -1)
+        fmt = lambda v: "unlimited" if v == unlimited else v
+        self._fill(self.limits_tree, limits if limits == ACCESS_DENIED else
+                   [(name, fmt(soft), fmt(hard)) for name, (soft, hard) in limits])
+        self.maps_text.delete(1.0, tk.END)
+        maps = d['memory_maps']
+        if maps == ACCESS_DENIED:
+            self.maps_text.insert(tk.END, ACCESS_DENIED)
+            return
+        total = lambda k: sum(m.get(k) or 0 for m in maps)
+        lines = [f"{len(maps)} mappings   RSS {human_bytes(total('rss'))}   PSS {human_bytes(total('pss'))}"
+                 f"   Swap {human_bytes(total('swap'))}", "", f"{'RSS':>10} {'PSS':>10} {'Swap':>10}  Path"]
+        for m in sorted(maps, key=lambda m: m.get('rss') or 0, reverse=True)[:25]:
+            lines.append(f"{human_bytes(m.get('rss')):>10} {human_bytes(m.get('pss')):>10} {human_bytes(m.get('swap')):>10}  {m.get('path')}")
+        self.maps_text.insert(tk.END, "\n".join(lines))
+
+    def _update_chart(self):
+        self.cpu_ax.clear()
+        self.rss_ax.clear()
+        x = list(range(len(self.cpu_history)))
+        self.cpu_ax.plot(x, self.cpu_history, label="CPU %")
+        self.rss_ax.plot(x, self.rss_history, label="RSS MB", color="orange")
+        self.cpu_ax.set_ylim(bottom=0)
+        self.rss_ax.set_ylim(bottom=0)
+        self.cpu_ax.legend(fontsize=8)
+        self.rss_ax.legend(fontsize=8)
+        self.canvas.draw_idle()
+
+# -----------------------
+# Terminal UI (curses)
+# -----------------------
+SPARK_CHARS = " ▁▂▃▄▅▆▇█"
+
+def sparkline(values, width, vmax=100.0):
+    """Render the last `width` values as a one-line unicode sparkline."""
+    values = values[-width:]
+    out = []
+    for v in values:
+        idx = int(round((min(max(v or 0.0, 0.0), vmax) / vmax) * (len(SPARK_CHARS) - 1)))
+        out.append(SPARK_CHARS[idx])
+    return "".join(out)
+
+def bar(percent, width):
+    """Render a [|||   ] style usage bar of the given inner width."""
+    percent = min(max(percent or 0.0, 0.0), 100.0)
+    filled = int(round(percent / 100.0 * width))
+    return "[" + "|" * filled + " " * (width - filled) + "]"
+
+class CursesApp:
+    """Terminal frontend fed by the same snapshot queue as the Tk app."""
+    PANELS = ["Overview", "Disk", "Network", "Processes"]
+
+    def __init__(self, stdscr, interval=1.0, full_memory_top=FULL_MEMORY_TOP_N):
+        self.scr = stdscr
+        self.interval = interval
+        self.sampler = SystemSampler(full_memory_top)
+        self.queue = queue.Queue()
+        self.stop_event = threading.Event()
+        self.latest_snapshot = None
+        self.panel = 0
+        self.sort_by = "cpu"
+        self.query = ""
+        self.selected_pid = None
+        self.cursor = 0
+        self.scroll = 0
+        self.message = "Ready"
+        self.cpu_history = []
+        self.mem_history = []
+
+    def run(self):
+        try:
+            curses.curs_set(0)
+        except curses.error:
+            pass
+        self.scr.keypad(True)
+        self.scr.timeout(200)
+        start_sampler_thread(self.sampler, self.queue, self.interval, self.stop_event)
+        try:
+            while True:
+                self._process_queue()
+                self._draw()
+                if not self._handle_key(self.scr.getch()):
+                    break
+        finally:
+            self.stop_event.set()
+
+    # ---------------- snapshot handling ----------------
+    def _process_queue(self):
+        while not self.queue.empty():
+            s = self.queue.get_nowait()
+            if "error" in s:
+                self.message = "Sampler error: " + s["error"]
+                continue
+            self.latest_snapshot = s
+            self.cpu_history.append(s['cpu']['total_percent'])
+            self.mem_history.append(s['memory']['virtual']['percent'])
+            self.cpu_history = self.cpu_history[-CHART_POINTS:]
+            self.mem_history = self.mem_history[-CHART_POINTS:]
+            self.message = f"Last update: {s['timestamp']}"
+
+    def _visible_processes(self):
+        if not self.latest_snapshot:
+            return []
+        self.sampler.full_memory = needs_full_memory(self.sort_by, self.query)
+        plist = list(self.latest_snapshot['processes'])
+        sort_processes(plist, self.sort_by)
+        try:
+            return filter_processes(plist, self.query)
+        except ProcessFilterError:
+            return plist
+
+    # ---------------- drawing ----------------
+    def _put(self, y, x, text, attr=0):
+        h, w = self.scr.getmaxyx()
+        if y < 0 or y >= h or x >= w:
+            return
+        try:
+            self.scr.addnstr(y, x, text, w - x - 1, attr)
+        except curses.error:
+            pass
+
+    def _draw(self):
+        self.scr.erase()
+        h, w = self.scr.getmaxyx()
+        self._put(0, 0, f" {APP_NAME} {APP_VERSION} ", curses.A_BOLD)
+        x = len(APP_NAME) + len(APP_VERSION) + 4
+        for i, name in enumerate(self.PANELS):
+            label = f" {i + 1}:{name} "
+            self._put(0, x, label, curses.A_REVERSE if i == self.panel else 0)
+            x += len(label) + 1
+        if not self.latest_snapshot:
+            self._put(2, 2, "Waiting for first snapshot...")
         else:
-            plist.sort(key=lambda x: (x['cpu_percent'] or 0), reverse=True)
-        query = self.proc_search.get().strip().lower()
-        if query:
-            plist = [p for p in plist if query in (p['name'] or "").lower()]
-        for i in self.proc_tree.get_children():
-            self.proc_tree.delete(i)
-        for p in plist:
-            self.proc_tree.insert("", tk.END, values=(
-                p['pid'], p['name'], p.get('username'), f"{p.get('cpu_percent',0):.1f}", f"{p.get('memory_percent',0):.1f}", human_bytes(p.get('memory_rss')), p.get('status')
-            ))
+            draw = [self._draw_overview, self._draw_disk, self._draw_network, self._draw_processes][self.panel]
+            draw(2, h - 3, w)
+        help_text = "Tab/1-4 panels  q quit"
+        if self.panel == 3:
+            help_text = "↑↓ PgUp/PgDn select  s sort  / filter  k terminate  " + help_text
+        self._put(h - 2, 0, help_text, curses.A_DIM)
+        self._put(h - 1, 0, self.message.ljust(w - 1), curses.A_REVERSE)
+        self.scr.refresh()
+
+    def _draw_overview(self, y, bottom, w):
+        s = self.latest_snapshot
+        cpu = s['cpu']
+        vm = s['memory']['virtual']
+        sw = s['memory']['swap']
+        bw = max(10, min(50, w - 30))
+        self._put(y, 2, f"CPU  {bar(cpu['total_percent'], bw)} {cpu['total_percent']:5.1f}%  ({cpu['count']} cores)")
+        self._put(y + 1, 2, f"MEM  {bar(vm['percent'], bw)} {vm['percent']:5.1f}%  ({human_bytes(vm['used'])} / {human_bytes(vm['total'])})")
+        self._put(y + 2, 2, f"SWAP {bar(sw['percent'], bw)} {sw['percent']:5.1f}%  ({human_bytes(sw['used'])} / {human_bytes(sw['total'])})")
+        la = cpu.get('load_avg') or (0.0, 0.0, 0.0)
+        self._put(y + 3, 2, f"Load average: {la[0]:.2f} {la[1]:.2f} {la[2]:.2f}")
+        self._put(y + 5, 2, "CPU history", curses.A_BOLD)
+        self._put(y + 6, 2, sparkline(self.cpu_history, w - 6))
+        self._put(y + 7, 2, "Memory history", curses.A_BOLD)
+        self._put(y + 8, 2, sparkline(self.mem_history, w - 6))
+        row = y + 10
+        self._put(row, 2, "Per core", curses.A_BOLD)
+        for i, pct in enumerate(cpu['per_core']):
+            if row + 1 + i >= bottom:
+                break
+            self._put(row + 1 + i, 2, f"#{i:<3} {bar(pct, bw)} {pct:5.1f}%")
+
+    def _draw_disk(self, y, bottom, w):
+        s = self.latest_snapshot
+        devices = s['disk'].get('devices') or []
+        self._put(y, 2, f"{'Device':<12}{'Read/s':>12}{'Write/s':>12}{'R IOPS':>9}{'W IOPS':>9}{'R lat':>10}{'W lat':>10}{'Util':>7}  Mounted",
+                  curses.A_BOLD)
+        for i, d in enumerate(devices):
+            if y + 1 + i >= bottom:
+                break
+            rates = d.get('rates') or {}
+            self._put(y + 1 + i, 2, f"{d['name'][:11]:<12}{human_bytes(rates.get('read_bytes_per_sec') or 0) + '/s':>12}"
+                      f"{human_bytes(rates.get('write_bytes_per_sec') or 0) + '/s':>12}"
+                      f"{rate_str(d.get('read_iops')):>9}{rate_str(d.get('write_iops')):>9}"
+                      f"{rate_str(d.get('read_latency_ms'), '{:.2f}ms'):>10}{rate_str(d.get('write_latency_ms'), '{:.2f}ms'):>10}"
+                      f"{rate_str(d.get('util_percent'), '{:.0f}%'):>7}  {', '.join(d.get('partitions') or [])}")
+        y += len(devices) + 2 if devices else 0
+        self._put(y, 2, f"{'Mountpoint':<24}{'FS':<8}{'Total':>10}{'Used':>10}{'Free':>10}{'%':>7}", curses.A_BOLD)
+        for i, p in enumerate(s['disk']['partitions']):
+            if y + 1 + i >= bottom:
+                break
+            usage = p.get('usage') or {}
+            pct = usage.get('percent')
+            self._put(y + 1 + i, 2, f"{(p.get('mountpoint') or '')[:23]:<24}{(p.get('fstype') or '')[:7]:<8}"
+                      f"{human_bytes(usage.get('total')):>10}{human_bytes(usage.get('used')):>10}"
+                      f"{human_bytes(usage.get('free')):>10}{(f'{pct}%' if pct is not None else 'N/A'):>7}")
+
+    def _draw_network(self, y, bottom, w):
+        net = self.latest_snapshot['network']
+        io = net['io']
+        rates = net.get('rates') or {}
+        lines = network_lines(io, rates)
+        for i, line in enumerate(lines):
+            self._put(y + i, 2, line)
+        row = y + len(lines) + 1
+        self._put(row, 2, f"{'Interface':<16}{'State':<6}{'Speed':>10}{'Recv/s':>12}{'Sent/s':>12}{'Errors':>8}{'Drops':>8}  Address",
+                  curses.A_BOLD)
+        for i, n in enumerate(net.get('interfaces') or []):
+            if row + 1 + i >= bottom:
+                break
+            nio = n.get('io') or {}
+            nrates = n.get('rates') or {}
+            addr = next((a['address'] for a in n.get('addresses') or [] if a['family'] == "IPv4"), "")
+            self._put(row + 1 + i, 2,
+                      f"{n['name'][:15]:<16}{('up' if n.get('isup') else 'down'):<6}"
+                      f"{(str(n['speed_mbps']) + ' Mb/s' if n.get('speed_mbps') else 'N/A'):>10}"
+                      f"{human_bytes(nrates.get('bytes_recv_per_sec') or 0) + '/s':>12}"
+                      f"{human_bytes(nrates.get('bytes_sent_per_sec') or 0) + '/s':>12}"
+                      f"{(nio.get('errin') or 0) + (nio.get('errout') or 0):>8}"
+                      f"{(nio.get('dropin') or 0) + (nio.get('dropout') or 0):>8}  {addr}")
+
+    def _draw_processes(self, y, bottom, w):
+        plist = self._visible_processes()
+        title = f"Sort: {self.sort_by}   Filter: {self.query or '-'}   {len(plist)} processes"
+        self._put(y, 2, title, curses.A_BOLD)
+        header = f"{'PID':>7} {'Name':<24} {'User':<12} {'CPU%':>6} {'TIME+':>10} {'Mem%':>6} {'RSS':>10} {'Read/s':>11} {'Write/s':>11} {'Status':<10}"
+        self._put(y + 1, 2, header, curses.A_UNDERLINE)
+        rows = max(1, bottom - (y + 2))
+        self._sync_cursor(plist)
+        if self.cursor < self.scroll:
+            self.scroll = self.cursor
+        elif self.cursor >= self.scroll + rows:
+            self.scroll = self.cursor - rows + 1
+        for i, p in enumerate(plist[self.scroll:self.scroll + rows]):
+            idx = self.scroll + i
+            line = (f"{p['pid']:>7} {(p['name'] or '')[:24]:<24} {(p.get('username') or '')[:12]:<12} "
+                    f"{pct_str(p.get('cpu_percent')):>6} {format_cpu_time(p.get('cpu_time')):>10} {(p.get('memory_percent') or 0):6.1f} "
+                    f"{human_bytes(p.get('memory_rss')):>10} {io_rate_str(p.get('io_read_per_sec')):>11} "
+                    f"{io_rate_str(p.get('io_write_per_sec')):>11} {(p.get('status') or ''):<10}")
+            self._put(y + 2 + i, 2, line.ljust(w - 4), curses.A_REVERSE if idx == self.cursor else 0)
+
+    def _sync_cursor(self, plist):
+        """Keep the cursor on the selected PID across refreshes and re-sorts."""
+        if not plist:
+            self.cursor = 0
+            self.selected_pid = None
+            return
+        if self.selected_pid is not None:
+            for i, p in enumerate(plist):
+                if p['pid'] == self.selected_pid:
+                    self.cursor = i
+                    break
+        self.cursor = min(max(self.cursor, 0), len(plist) - 1)
+        self.selected_pid = plist[self.cursor]['pid']
+
+    # ---------------- input ----------------
+    def _handle_key(self, key):
+        if key == -1:
+            return True
+        if key in (ord('q'), ord('Q')):
+            return False
+        if key in (ord('\t'), curses.KEY_RIGHT):
+            self.panel = (self.panel + 1) % len(self.PANELS)
+        elif key in (curses.KEY_BTAB, curses.KEY_LEFT):
+            self.panel = (self.panel - 1) % len(self.PANELS)
+        elif ord('1') <= key <= ord(str(len(self.PANELS))):
+            self.panel = key - ord('1')
+        elif self.panel == 3:
+            self._handle_process_key(key)
+        return True
+
+    def _move_cursor(self, delta):
+        plist = self._visible_processes()
+        if not plist:
+            return
+        self._sync_cursor(plist)
+        self.cursor = min(max(self.cursor + delta, 0), len(plist) - 1)
+        self.selected_pid = plist[self.cursor]['pid']
+
+    def _handle_process_key(self, key):
+        page = max(1, self.scr.getmaxyx()[0] - 7)
+        moves = {curses.KEY_UP: -1, curses.KEY_DOWN: 1, curses.KEY_PPAGE: -page, curses.KEY_NPAGE: page,
+                 curses.KEY_HOME: -10**9, curses.KEY_END: 10**9}
+        if key in moves:
+            self._move_cursor(moves[key])
+        elif key == ord('s'):
+            self.sort_by = SORT_KEYS[(SORT_KEYS.index(self.sort_by) + 1) % len(SORT_KEYS)]
+        elif key == ord('/'):
+            query = self._prompt("Filter: ")
+            try:
+                parse_process_filter(query)
+            except ProcessFilterError as e:
+                self.message = f"Filter error: {e}"
+                return
+            self.query = query
+            self.cursor = 0
+            self.selected_pid = None
+        elif key == ord('k'):
+            self.kill_selected_process()
+
+    def _prompt(self, label):
+        h, w = self.scr.getmaxyx()
+        self._put(h - 1, 0, label.ljust(w - 1), curses.A_REVERSE)
+        curses.echo()
+        try:
+            curses.curs_set(1)
+        except curses.error:
+            pass
+        self.scr.timeout(-1)
+        try:
+            raw = self.scr.getstr(h - 1, len(label), max(1, w - len(label) - 2))
+        finally:
+            curses.noecho()
+            try:
+                curses.curs_set(0)
+            except curses.error:
+                pass
+            self.scr.timeout(200)
+        return raw.decode(errors="replace").strip()
 
     def kill_selected_process(self):
-        sel = self.proc_tree.selection()
-        if not sel:
-            messagebox.showwarning(APP_NAME, "Select a process to kill.")
+        plist = self._visible_processes()
+        if not plist or self.selected_pid is None:
+            self.message = "Select a process to kill."
+            return
+        proc = next((p for p in plist if p['pid'] == self.selected_pid), None)
+        if proc is None:
+            self.message = "Select a process to kill."
+            return
+        pid, name = proc['pid'], proc['name']
+        if self._prompt(f"Terminate process {name} (PID {pid})? [y/N] ").lower() not in ("y", "yes"):
+            self.message = "Cancelled"
             return
-        pid = int(self.proc_tree.item(sel[0],'values')[0])
-        name = self.proc_tree.item(sel[0],'values')[1]
-        if messagebox.askyesno(APP_NAME, f"Terminate process {name} (PID {pid})?"):
+        try:
+            terminate_process(pid)
+            self.message = f"Process {name} (PID {pid}) terminated."
+        except Exception as e:
+            self.message = f"Error: {e}"
+
+def run_tui(interval=1.0, full_memory_top=FULL_MEMORY_TOP_N):
+    """Run the curses frontend until the user quits."""
+    if curses is None:
+        sys.exit(f"{APP_NAME}: terminal UI unavailable (no curses module); try --headless")
+    curses.wrapper(lambda stdscr: CursesApp(stdscr, interval, full_memory_top).run())
+    return 0
+
+# -----------------------
+# Headless mode
+# -----------------------
+def event_line(e):
+    """One line describing a process started/exited event."""
+    text = f"{e['time']}  {e['event']:<7}  {e.get('name')} (PID {e['pid']})"
+    if e['event'] == "exited":
+        text += (f"  lived {format_cpu_time(e.get('lifetime'))}  peak RSS {human_bytes(e.get('peak_rss'))}"
+                 f"  CPU {format_cpu_time(e.get('cpu_time'))}")
+    return text + (f"  {e['cmdline']}" if e.get('cmdline') else "")
+
+def snapshot_summary(s):
+    """Return a compact one-line summary of a snapshot, as shown in the Logs tab."""
+    if "error" in s:
+        return f"{s['timestamp']}  ERROR {s['error']}"
+    net = s['network'].get('rates') or {}
+    disk = s['disk'].get('rates') or {}
+    events = s.get('events') or []
+    started = sum(e['event'] == "started" for e in events)
+    return (f"{s['timestamp']}  CPU {s['cpu']['total_percent']:.1f}%  MEM {s['memory']['virtual']['percent']:.1f}%"
+            f"  NET ↑{human_bytes(net.get('bytes_sent_per_sec') or 0)}/s ↓{human_bytes(net.get('bytes_recv_per_sec') or 0)}/s"
+            f"  DISK R {human_bytes(disk.get('read_bytes_per_sec') or 0)}/s W {human_bytes(disk.get('write_bytes_per_sec') or 0)}/s"
+            f"  PROCS {len(s['processes'])}" + (f" (+{started} -{len(events) - started})" if events else ""))
+
+def run_headless(interval=1.0, as_json=False, count=None, out=None, recorder=None, top_n=None,
+                 full_memory_top=FULL_MEMORY_TOP_N):
+    """Drive the sampler loop without Tk, printing one line per snapshot until stopped.
+    If a recorder is given, every snapshot is also streamed to its session file."""
+    out = out or sys.stdout
+    sampler = SystemSampler(full_memory_top)
+    sampler.full_memory = as_json or recorder is not None  # the text line doesn't show them
+    if recorder:
+        recorder.start()
+    stop = threading.Event()
+
+    def _stop(signum, frame):
+        stop.set()
+    signal.signal(signal.SIGINT, _stop)
+    signal.signal(signal.SIGTERM, _stop)
+
+    taken = 0
+    try:
+        while not stop.is_set():
+            started = time.monotonic()
             try:
-                psutil.Process(pid).terminate()
-                messagebox.showinfo(APP_NAME, f"Process {name} (PID {pid}) terminated.")
-                self.refresh_processes()
+                snap = sampler.snapshot()
             except Exception as e:
-                messagebox.showerror(APP_NAME, f"Error: {e}")
+                snap = {"timestamp": now_iso(), "error": str(e)}
+            if recorder and "error" not in snap:
+                try:
+                    recorder.write(snap)
+                except OSError as e:
+                    # like the GUI: stop recording but keep sampling
+                    print(f"Recording stopped: {e}", file=sys.stderr)
+                    recorder.stop()
+                    recorder = None
+            try:
+                if as_json:
+                    out.write(json.dumps(trim_processes(snap, top_n)) + "\n")
+                else:
+                    out.write("".join(line + "\n" for line in [snapshot_summary(snap)] + ["  " + event_line(e) for e in snap.get('events') or []]))
+                out.flush()
+            except BrokenPipeError:
+                break
+            taken += 1
+            if count and taken >= count:
+                break
+            stop.wait(max(0.0, interval - (time.monotonic() - started)))
+    finally:
+        if recorder:
+            recorder.stop()
+    return 0
 
-# ---------------- main entry ----------------
-if __name__ == "__main__":
+def parse_args(argv=None):
+    parser = argparse.ArgumentParser(prog="main_star.py", description=f"{APP_NAME} {APP_VERSION}")
+    parser.add_argument("--headless", action="store_true", help="run without a GUI and print snapshots to stdout")
+    parser.add_argument("--tui", action="store_true", help="run the curses terminal UI instead of the Tk GUI")
+    parser.add_argument("--json", action="store_true", help="in headless mode, print each full snapshot as one JSON line")
+    parser.add_argument("--interval", type=float, default=UPDATE_INTERVAL_MS / 1000.0, help="seconds between snapshots (default: %(default)s)")
+    parser.add_argument("--count", type=int, default=None, help="stop after this many snapshots")
+    parser.add_argument("--export-top", type=int, default=None, metavar="N",
+                        help="keep only the top N processes (by CPU) in JSON output, snapshots and recordings")
+    parser.add_argument("--full-memory-top", type=int, default=FULL_MEMORY_TOP_N, metavar="N",
+                        help="read USS/PSS/swap (slow) for the N largest processes by RSS only; 0 for all (default: %(default)s)")
+    rec = parser.add_argument_group("session recording")
+    rec.add_argument("--record", action="store_true", help="in headless mode, also stream snapshots to a session file")
+    rec.add_argument("--session-dir", default=SESSION_DIR, help="where session files are written (default: %(default)s)")
+    rec.add_argument("--compress", action="store_true", help="gzip-compress session files")
+    rec.add_argument("--rotate-mb", type=float, default=SESSION_ROTATE_MB, help="start a new session file after this many MB (default: %(default)s)")
+    rec.add_argument("--rotate-minutes", type=float, default=SESSION_ROTATE_MIN, help="start a new session file after this many minutes (default: %(default)s)")
+    rec.add_argument("--keep", type=int, default=SESSION_KEEP, help="number of session files to keep (default: %(default)s)")
+    return parser.parse_args(argv)
+
+def recorder_from_args(args):
+    return SessionRecorder(directory=args.session_dir, compress=args.compress, rotate_mb=args.rotate_mb,
+                           rotate_minutes=args.rotate_minutes, keep=args.keep, top_n=args.export_top)
+
+def run_gui(recorder=None, export_top_n=None, full_memory_top=FULL_MEMORY_TOP_N):
+    if GUI_IMPORT_ERROR is not None:
+        sys.exit(f"{APP_NAME}: GUI unavailable ({GUI_IMPORT_ERROR}); try --tui or --headless")
     root = tk.Tk()
-    app = VanillaLOOKApp(root)
+    app = VanillaLOOKApp(root, recorder=recorder, export_top_n=export_top_n, full_memory_top=full_memory_top)
     root.geometry("1200x700")
     root.mainloop()
+
+# ---------------- main entry ----------------
+if __name__ == "__main__":
+    args = parse_args()
+    if args.headless:
+        sys.exit(run_headless(interval=args.interval, as_json=args.json, count=args.count,
+                              recorder=recorder_from_args(args) if args.record else None, top_n=args.export_top,
+                              full_memory_top=args.full_memory_top))
+    if args.tui:
+        sys.exit(run_tui(interval=args.interval, full_memory_top=args.full_memory_top))
+    run_gui(recorder=recorder_from_args(args), export_top_n=args.export_top, full_memory_top=args.full_memory_top)
@@ -0,0 +1,382 @@
+"""Unit tests for the pure helpers in main_star.py. They need psutil installed but not Tk,
+and never depend on what is running on the machine.
+
+Run with: python -m unittest discover tests
+"""
+import gzip
+import json
+import os
+import sys
+import tempfile
+import unittest
+from unittest import mock
+
+sys.path.insert(0, os.path.join(os.path.dirname(os.path.abspath(__file__)), ".."))
+import main_star  # noqa: E402
+
+
+class RateEngineTest(unittest.TestCase):
+    def setUp(self):
+        self.rates = main_star.RateEngine()
+
+    def test_first_sample_has_no_rates(self):
+        r = self.rates.update("net", {"bytes_sent": 100}, now=10.0)
+        self.assertIsNone(r["bytes_sent_per_sec"])
+        self.assertIsNone(r["interval_sec"])
+
+    def test_rate_uses_elapsed_time(self):
+        self.rates.update("net", {"bytes_sent": 100}, now=10.0)
+        r = self.rates.update("net", {"bytes_sent": 400}, now=12.0)
+        self.assertEqual(r["bytes_sent_per_sec"], 150.0)
+        self.assertEqual(r["interval_sec"], 2.0)
+
+    def test_32_bit_wrap_is_unwrapped(self):
+        self.rates.update("nic", {"bytes_recv": 2 ** 32 - 100}, now=0.0)
+        r = self.rates.update("nic", {"bytes_recv": 50}, now=1.0)
+        self.assertEqual(r["bytes_recv_per_sec"], 150.0)
+
+    def test_reset_counter_gives_no_rate(self):
+        self.rates.update("disk", {"read_bytes": 10 ** 12}, now=0.0)
+        r = self.rates.update("disk", {"read_bytes": 5}, now=1.0)
+        self.assertIsNone(r["read_bytes_per_sec"])
+        # ...and the reset value is the baseline for the next sample
+        r = self.rates.update("disk", {"read_bytes": 15}, now=2.0)
+        self.assertEqual(r["read_bytes_per_sec"], 10.0)
+
+    def test_no_time_elapsed_gives_no_rate(self):
+        self.rates.update("net", {"bytes_sent": 100}, now=5.0)
+        for now in (5.0, 4.0):
+            r = self.rates.update("net", {"bytes_sent": 200}, now=now)
+            self.assertIsNone(r["bytes_sent_per_sec"])
+            self.assertIsNone(r["interval_sec"])
+
+    def test_sources_are_independent_and_pruned(self):
+        self.rates.update(("nic", "eth0"), {"bytes_sent": 0}, now=0.0)
+        self.rates.update(("nic", "eth1"), {"bytes_sent": 0}, now=0.0)
+        self.rates.prune("nic", {("nic", "eth0")})
+        self.assertEqual(self.rates.update(("nic", "eth0"), {"bytes_sent": 10}, now=1.0)["bytes_sent_per_sec"], 10.0)
+        self.assertIsNone(self.rates.update(("nic", "eth1"), {"bytes_sent": 10}, now=1.0)["bytes_sent_per_sec"])
+
+    def test_non_numeric_counters_are_ignored(self):
+        r = self.rates.update("net", {"bytes_sent": 1, "name": "eth0", "missing": None}, now=0.0)
+        self.assertNotIn("name_per_sec", r)
+        self.assertNotIn("missing_per_sec", r)
+
+
+class FakeProcess:
+    """Stands in for psutil.Process: the machine's processes are {pid: create_time}."""
+    table = {}
+
+    def __init__(self, pid):
+        if pid not in self.table:
+            raise main_star.psutil.NoSuchProcess(pid)
+        self.pid = pid
+        self._ctime = self.table[pid]
+
+    def create_time(self):
+        return self._ctime
+
+    def is_running(self):
+        return self.table.get(self.pid) == self._ctime
+
+
+class IterProcessesTest(unittest.TestCase):
+    def setUp(self):
+        self.sampler = main_star.SystemSampler.__new__(main_star.SystemSampler)  # skip the psutil counters
+        self.sampler.proc_cache = {}
+
+    def scan(self, table):
+        FakeProcess.table = table
+        with mock.patch.object(main_star.psutil, "pids", lambda: sorted(table), create=True), \
+                mock.patch.object(main_star.psutil, "Process", FakeProcess):
+            return [(p.pid, ctime, is_new) for p, ctime, is_new in self.sampler._iter_processes()]
+
+    def test_processes_are_cached_between_scans(self):
+        self.assertEqual(self.scan({1: 100.0, 2: 200.0}), [(1, 100.0, True), (2, 200.0, True)])
+        first = self.sampler.proc_cache[1][0]
+        self.assertEqual(self.scan({1: 100.0, 2: 200.0}), [(1, 100.0, False), (2, 200.0, False)])
+        self.assertIs(self.sampler.proc_cache[1][0], first)
+
+    def test_reused_pid_gets_a_fresh_process(self):
+        self.scan({7: 100.0})
+        old = self.sampler.proc_cache[7][0]
+        self.assertEqual(self.scan({7: 500.0}), [(7, 500.0, True)])
+        self.assertIsNot(self.sampler.proc_cache[7][0], old)
+        self.assertEqual(self.sampler.proc_cache[7][1], 500.0)
+
+    def test_exited_processes_leave_the_cache(self):
+        self.scan({1: 100.0, 2: 200.0})
+        self.assertEqual(self.scan({1: 100.0}), [(1, 100.0, False)])
+        self.assertEqual(set(self.sampler.proc_cache), {1})
+
+    def test_process_gone_between_listing_and_opening_is_skipped(self):
+        FakeProcess.table = {}
+        with mock.patch.object(main_star.psutil, "pids", lambda: [3], create=True), \
+                mock.patch.object(main_star.psutil, "Process", FakeProcess):
+            self.assertEqual(list(self.sampler._iter_processes()), [])
+        self.assertEqual(self.sampler.proc_cache, {})
+
+
+
+class DisplaySortKeyTest(unittest.TestCase):
+    key = staticmethod(main_star.display_sort_key)
+
+    def test_sizes_compare_by_bytes(self):
+        self.assertLess(self.key("900.0KB"), self.key("1.5MB"))
+        self.assertLess(self.key("1023.0B"), self.key("1.0KB"))
+        self.assertEqual(self.key(main_star.human_bytes(3 * 1024 ** 3)), (0, 3.0 * 1024 ** 3))
+
+    def test_rates_and_leak_growth(self):
+        self.assertLess(self.key("10.0KB/s"), self.key("2.0MB/s"))
+        self.assertEqual(self.key("↑ 2.0MB/h"), (0, 2.0 * 1024 ** 2))
+
+    def test_numbers_with_units(self):
+        self.assertEqual(self.key("12.3%"), (0, 12.3))
+        self.assertEqual(self.key("4.5 ms"), (0, 4.5))
+        self.assertEqual(self.key("1000 Mb/s"), (0, 1000.0))
+        self.assertLess(self.key("9"), self.key("10"))
+        self.assertEqual(self.key(-3), (0, -3.0))
+
+    def test_cpu_times(self):
+        self.assertEqual(self.key("1:02.33"), (0, 62.33))
+        self.assertEqual(self.key("2:00:05"), (0, 7205.0))
+        self.assertLess(self.key(main_star.format_cpu_time(3599.5)), self.key(main_star.format_cpu_time(3600)))
+
+    def test_blanks_and_text(self):
+        for blank in (None, "", "  ", "-", "--", "N/A"):
+            self.assertIsNone(self.key(blank))
+        self.assertEqual(self.key("Firefox"), (1, "firefox"))
+        self.assertLess(self.key("42"), self.key("abc"))  # numbers before text
+
+
+class SortTableRowsTest(unittest.TestCase):
+    def test_sorts_by_column_with_blanks_last(self):
+        rows = [("a", "", "", ("a", "1.0KB")), ("b", "", "", ("b", "--")), ("c", "", "", ("c", "2.0MB"))]
+        self.assertEqual([r[0] for r in main_star.sort_table_rows(rows, 1, True)], ["c", "a", "b"])
+        self.assertEqual([r[0] for r in main_star.sort_table_rows(rows, 1, False)], ["a", "c", "b"])
+
+    def test_ties_fall_back_to_the_secondary_column(self):
+        rows = [("2", "", "", ("zeta", "5")), ("1", "", "", ("Alpha", "5")), ("3", "", "", ("beta", "7"))]
+        self.assertEqual([r[0] for r in main_star.sort_table_rows(rows, 1, True)], ["3", "1", "2"])
+
+    def test_children_stay_under_their_parent(self):
+        rows = [("p1", "", "", ("p1", "1")), ("c1", "p1", "", ("c1", "9")), ("c2", "p1", "", ("c2", "3")),
+                ("p2", "", "", ("p2", "5")), ("c3", "p2", "", ("c3", "0"))]
+        out = main_star.sort_table_rows(rows, 1, True)
+        self.assertEqual([r[0] for r in out], ["p2", "c3", "p1", "c1", "c2"])
+
+    def test_short_rows_sort_as_blank(self):
+        rows = [("a", "", "", ("a",)), ("b", "", "", ("b", "3"))]
+        self.assertEqual([r[0] for r in main_star.sort_table_rows(rows, 1, False)], ["b", "a"])
+
+
+
+PROCS = [
+    {"pid": 1, "ppid": 0, "name": "systemd", "username": "root", "cmdline": "/sbin/init splash",
+     "cpu_percent": 0.1, "memory_rss": 12 * 1024 ** 2, "status": "sleeping", "leak_rate": None},
+    {"pid": 420, "ppid": 1, "name": "Firefox", "username": "ana", "cmdline": "/usr/lib/firefox/firefox -P work",
+     "cpu_percent": 35.0, "memory_rss": 2 * 1024 ** 3, "status": "running", "leak_rate": 8 * 1024 ** 2},
+    {"pid": 421, "ppid": 420, "name": "firefox-bin", "username": "ana", "cmdline": None,
+     "cpu_percent": None, "memory_rss": None, "status": "sleeping", "leak_rate": None},
+]
+
+
+class ProcessFilterTest(unittest.TestCase):
+    def pids(self, query):
+        return [p["pid"] for p in main_star.filter_processes(PROCS, query)]
+
+    def test_empty_query_keeps_everything(self):
+        self.assertEqual(self.pids(""), [1, 420, 421])
+        self.assertEqual(self.pids("   "), [1, 420, 421])
+
+    def test_bare_word_is_a_case_insensitive_name_match(self):
+        self.assertEqual(self.pids("FIRE"), [420, 421])
+
+    def test_text_operators(self):
+        self.assertEqual(self.pids("user:an"), [420, 421])
+        self.assertEqual(self.pids("name=firefox"), [420])
+        self.assertEqual(self.pids("status!=sleeping"), [420])
+        self.assertEqual(self.pids("cmd~^/usr/.*-P"), [420])
+
+    def test_number_and_size_comparisons(self):
+        self.assertEqual(self.pids("cpu>10"), [420])
+        self.assertEqual(self.pids("rss>=1G"), [420])
+        self.assertEqual(self.pids("rss<100MB"), [1])
+        self.assertEqual(self.pids("ppid=420"), [421])
+        self.assertEqual(self.pids("leak>0"), [420])
+
+    def test_missing_values_never_match_comparisons(self):
+        self.assertNotIn(421, self.pids("cpu<1"))
+        self.assertNotIn(421, self.pids("cmd~."))
+
+    def test_terms_are_anded_and_can_be_negated(self):
+        self.assertEqual(self.pids("fire user:ana -name=firefox"), [421])
+        self.assertEqual(self.pids("!fire"), [1])
+
+    def test_quoted_values(self):
+        self.assertEqual(self.pids('cmd:"init splash"'), [1])
+        self.assertEqual(self.pids(r'cmd:"-P \"work"'), [])
+        self.assertEqual(self.pids('cmd:"-P work"'), [420])
+
+    def test_field_names_are_case_insensitive(self):
+        self.assertEqual(self.pids("PID=1"), [1])
+
+    def test_errors(self):
+        for query, message in [
+            ("bogus=1", "unknown field"),
+            ("cpu>lots", "not a number"),
+            ("name>3", "is text"),
+            ("cmd~(", "bad regex"),
+            ('cmd:"open', "unterminated quote"),
+        ]:
+            with self.subTest(query=query):
+                with self.assertRaisesRegex(main_star.ProcessFilterError, message):
+                    main_star.parse_process_filter(query)
+
+
+
+MB = 1024 ** 2
+
+
+def rss_points(minutes, mb_per_hour, start=100 * MB, wobble=0):
+    """[(time, rss)] every 30 s for minutes, growing mb_per_hour, alternately +/- wobble bytes."""
+    return [(t * 30.0, start + mb_per_hour * MB * t * 30 / 3600 + (wobble if t % 2 else -wobble))
+            for t in range(minutes * 2 + 1)]
+
+
+class LeakDetectorTest(unittest.TestCase):
+    def test_memory_trend_of_a_straight_line(self):
+        slope, r2 = main_star.memory_trend([(0, 100), (10, 200), (20, 300)])
+        self.assertAlmostEqual(slope, 10.0)
+        self.assertAlmostEqual(r2, 1.0)
+
+    def test_memory_trend_needs_three_points_and_some_change(self):
+        self.assertEqual(main_star.memory_trend([(0, 1), (10, 5)]), (0.0, 0.0))
+        self.assertEqual(main_star.memory_trend([(0, 7), (10, 7), (20, 7)]), (0.0, 0.0))
+        self.assertEqual(main_star.memory_trend([(5, 1), (5, 2), (5, 3)]), (0.0, 0.0))
+
+    def test_steady_growth_is_a_leak(self):
+        rate = main_star.leak_rate(rss_points(40, 10))
+        self.assertAlmostEqual(rate, 10 * MB, delta=1)
+
+    def test_too_short_a_history_is_not_a_leak(self):
+        self.assertIsNone(main_star.leak_rate(rss_points(main_star.LEAK_MIN_MINUTES - 1, 50)))
+        self.assertIsNone(main_star.leak_rate([]))
+
+    def test_slow_growth_is_not_a_leak(self):
+        self.assertIsNone(main_star.leak_rate(rss_points(60, 4)))
+
+    def test_shrinking_is_not_a_leak(self):
+        self.assertIsNone(main_star.leak_rate(rss_points(60, -20)))
+
+    def test_noisy_growth_below_the_r2_threshold_is_not_a_leak(self):
+        points = rss_points(40, 10, wobble=20 * MB)
+        self.assertLess(main_star.memory_trend(points)[1], main_star.LEAK_MIN_R2)
+        self.assertIsNone(main_star.leak_rate(points))
+
+    def test_small_noise_still_counts(self):
+        points = rss_points(40, 10, wobble=MB // 4)
+        self.assertGreaterEqual(main_star.memory_trend(points)[1], main_star.LEAK_MIN_R2)
+        self.assertIsNotNone(main_star.leak_rate(points))
+
+
+
+def snap(i):
+    return {"timestamp": f"2026-01-01T00:00:{i:02d}", "cpu": {"total_percent": float(i)}, "processes": []}
+
+
+class SessionFilesTest(unittest.TestCase):
+    def setUp(self):
+        self.tmp = tempfile.TemporaryDirectory()
+        self.dir = self.tmp.name
+
+    def tearDown(self):
+        self.tmp.cleanup()
+
+    def write_part(self, name, snaps, tail=""):
+        path = os.path.join(self.dir, name)
+        opener = gzip.open if name.endswith(".gz") else open
+        with opener(path, "wt", encoding="utf-8") as f:
+            f.write("".join(json.dumps(s) + "\n" for s in snaps) + tail)
+        return path
+
+    def test_session_file_key_orders_by_session_then_part(self):
+        names = ["session_20260101_101010-2_001.jsonl", "session_20260101_101010_010.jsonl",
+                 "session_20260101_101010_002.jsonl.gz", "session_20251231_235959_001.jsonl"]
+        self.assertEqual(sorted(names, key=main_star.session_file_key), [
+            "session_20251231_235959_001.jsonl", "session_20260101_101010_002.jsonl.gz",
+            "session_20260101_101010_010.jsonl", "session_20260101_101010-2_001.jsonl"])
+
+    def test_load_recording_joins_every_part_in_order(self):
+        self.write_part("session_20260101_101010_010.jsonl", [snap(3)])
+        first = self.write_part("session_20260101_101010_002.jsonl.gz", [snap(1), snap(2)])
+        self.write_part("session_20260101_101010-2_001.jsonl", [snap(9)])  # another session
+        loaded = main_star.load_recording(first)
+        self.assertEqual([s["cpu"]["total_percent"] for s in loaded], [1.0, 2.0, 3.0])
+
+    def test_load_recording_tolerates_a_truncated_tail_and_skips_errors(self):
+        path = self.write_part("session_20260101_101010_001.jsonl",
+                               [snap(1), {"timestamp": "x", "error": "boom"}, snap(2)], tail='{"timestamp": "2026')
+        self.assertEqual([s["cpu"]["total_percent"] for s in main_star.load_recording(path)], [1.0, 2.0])
+
+    def test_load_recording_reads_exported_arrays_and_single_snapshots(self):
+        path = os.path.join(self.dir, "export.json")
+        for data, expected in (([snap(1), snap(2)], 2), (snap(5), 1)):
+            with open(path, "w", encoding="utf-8") as f:
+                json.dump(data, f)
+            self.assertEqual(len(main_star.load_recording(path)), expected)
+
+    def test_retention_trims_old_parts_of_the_running_session_but_not_the_open_file(self):
+        self.write_part("session_20000101_000000_001.jsonl", [snap(0)])
+        rec = main_star.SessionRecorder(directory=self.dir, rotate_mb=1e-6, rotate_minutes=None, keep=3)
+        rec.start()
+        try:
+            for i in range(6):
+                rec.write(snap(i))  # every write fills the 1-byte limit and rotates
+            on_disk = sorted(os.listdir(self.dir), key=main_star.session_file_key)
+            self.assertEqual(len(on_disk), 3)
+            self.assertEqual(on_disk[-1], os.path.basename(rec.path))
+            self.assertTrue(all(n.startswith(f"session_{rec.session_id}_") for n in on_disk))
+            self.assertEqual(len(rec.missing_parts()), len(rec.files) - 3)
+        finally:
+            rec.stop()
+
+    def test_keep_zero_keeps_everything(self):
+        rec = main_star.SessionRecorder(directory=self.dir, rotate_mb=1e-6, rotate_minutes=None, keep=0)
+        rec.start()
+        for i in range(4):
+            rec.write(snap(i))
+        rec.stop()
+        self.assertEqual(len(os.listdir(self.dir)), 5)
+        self.assertEqual(rec.missing_parts(), [])
+        self.assertEqual(len(list(rec.snapshots())), 4)
+
+
+
+class SavedColumnsTest(unittest.TestCase):
+    def test_no_settings_shows_the_defaults(self):
+        self.assertEqual(main_star.saved_proc_columns({}), list(main_star.PROC_COLUMNS))
+
+    def test_order_and_extra_columns_are_kept(self):
+        hidden = [c for c in main_star.PROC_COLUMNS if c not in ("pid", "name")]
+        cols = main_star.saved_proc_columns({"columns": ["name", "pid", "uss"], "hidden": hidden})
+        self.assertEqual(cols, ["name", "pid", "uss"])
+
+    def test_new_defaults_appear_next_to_their_neighbour(self):
+        # saved before "leak" existed: everything else shown, nothing hidden
+        saved = [c for c in main_star.PROC_COLUMNS if c != "leak"] + ["ppid"]
+        cols = main_star.saved_proc_columns({"columns": saved, "hidden": []})
+        self.assertEqual(cols.index("leak"), cols.index("status") + 1)
+        self.assertEqual(cols[-1], "ppid")
+
+    def test_hidden_defaults_stay_hidden(self):
+        saved = [c for c in main_star.PROC_COLUMNS if c != "threads"]
+        self.assertNotIn("threads", main_star.saved_proc_columns({"columns": saved, "hidden": ["threads"]}))
+
+    def test_unknown_columns_are_dropped(self):
+        cols = main_star.saved_proc_columns({"columns": ["pid", "gone"], "hidden": list(main_star.PROC_COLUMNS[1:])})
+        self.assertEqual(cols, ["pid"])
+
+
+if __name__ == "__main__":
+    unittest.main()